@@ -0,0 +1,69 @@
+//! Conversion and pipeline microbenchmarks: arithmetic `adc_to_temp_x10`
+//! against the generated lookup table, and a full `Pipeline::step`.
+//!
+//! Each is measured on a steady input (one code repeated, the hot path of a
+//! settled sensor) and on a sweep over the whole 12-bit range.
+//!
+//! Run with `cargo bench --features bench`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use tsim::{adc_to_temp_x10, Pipeline, ADC_TO_TEMP_X10_LUT};
+
+/// Near the 100.0°C trip point.
+const STEADY_CODE: u16 = 3474;
+
+/// Every 12-bit code once, in a stride that defeats branch prediction.
+fn sweep() -> Vec<u16> {
+    (0..4096u32).map(|i| ((i * 2731) % 4096) as u16).collect()
+}
+
+fn conversion(c: &mut Criterion) {
+    let codes = sweep();
+    let mut group = c.benchmark_group("conversion");
+    group.bench_function("arithmetic/steady", |b| {
+        b.iter(|| adc_to_temp_x10(black_box(STEADY_CODE)))
+    });
+    group.bench_function("lut/steady", |b| {
+        b.iter(|| ADC_TO_TEMP_X10_LUT[black_box(STEADY_CODE) as usize])
+    });
+    group.bench_function("arithmetic/sweep", |b| {
+        b.iter(|| {
+            codes
+                .iter()
+                .map(|&adc| adc_to_temp_x10(black_box(adc)) as i32)
+                .sum::<i32>()
+        })
+    });
+    group.bench_function("lut/sweep", |b| {
+        b.iter(|| {
+            codes
+                .iter()
+                .map(|&adc| ADC_TO_TEMP_X10_LUT[black_box(adc) as usize] as i32)
+                .sum::<i32>()
+        })
+    });
+    group.finish();
+}
+
+fn pipeline(c: &mut Criterion) {
+    let codes = sweep();
+    let mut group = c.benchmark_group("pipeline_step");
+    group.bench_function("steady", |b| {
+        let mut pipeline = Pipeline::new(1000, 950);
+        b.iter(|| pipeline.step(black_box(STEADY_CODE)))
+    });
+    group.bench_function("sweep", |b| {
+        let mut pipeline = Pipeline::new(1000, 950);
+        b.iter(|| {
+            for &adc in &codes {
+                black_box(pipeline.step(black_box(adc)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, conversion, pipeline);
+criterion_main!(benches);
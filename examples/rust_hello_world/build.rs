@@ -1,5 +1,6 @@
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
@@ -32,4 +33,27 @@ fn main() {
 
     // Link the object file directly.
     println!("cargo:rustc-link-arg={}", obj.display());
+
+    write_adc_lut(&out_dir);
+}
+
+include!("src/adc_formula.rs");
+
+/// Emits `ADC_TO_TEMP_X10_LUT` from the conversion arithmetic itself.
+fn write_adc_lut(out_dir: &Path) {
+    println!("cargo:rerun-if-changed=src/adc_formula.rs");
+    println!("cargo:rustc-check-cfg=cfg(tsim_generated_lut)");
+    println!("cargo:rustc-cfg=tsim_generated_lut");
+
+    let mut lut = String::from("[\n");
+    for row in (0..4096u16).collect::<Vec<_>>().chunks(16) {
+        let entries: Vec<String> = row
+            .iter()
+            .map(|&adc| adc_formula_x10(adc).to_string())
+            .collect();
+        lut += &format!("    {},\n", entries.join(", "));
+    }
+    lut += "]\n";
+    fs::write(out_dir.join("adc_to_temp_x10_lut.rs"), lut)
+        .expect("failed to write ADC lookup table");
 }
@@ -0,0 +1,65 @@
+//! Conversion robustness: no panic and in-domain output for any ADC code,
+//! rounding mode and linear sensor configuration.
+//!
+//! Input layout (little-endian, missing bytes read as zero):
+//!
+//! | bytes  | field       |
+//! |--------|-------------|
+//! | 0..2   | ADC code    |
+//! | 2      | rounding    |
+//! | 3..7   | `uv_at_0c`  |
+//! | 7..11  | `uv_per_c`  |
+//! | 11..13 | `min_x10`   |
+//! | 13..15 | `max_x10`   |
+//! | 15..19 | `uv`        |
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tsim::{
+    adc_to_temp_x10, adc_to_temp_x100, adc_to_temp_x10_with_rounding, microvolts_to_temp_x10,
+    parse_adc_stream, read, LinearSensor, RoundingMode, TEMP_MAX_X10, TEMP_MIN_X10,
+};
+
+fn field<const K: usize>(data: &[u8], at: usize) -> [u8; K] {
+    let mut bytes = [0u8; K];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = data.get(at + i).copied().unwrap_or(0);
+    }
+    bytes
+}
+
+fuzz_target!(|data: &[u8]| {
+    let domain = TEMP_MIN_X10..=TEMP_MAX_X10;
+    let adc = u16::from_le_bytes(field(data, 0));
+
+    let temp_x10 = adc_to_temp_x10(adc);
+    assert!(domain.contains(&temp_x10));
+    assert!((TEMP_MIN_X10 as i32 * 10..=TEMP_MAX_X10 as i32 * 10).contains(&adc_to_temp_x100(adc)));
+    let _ = read(adc);
+
+    let mode = match field::<1>(data, 2)[0] % 3 {
+        0 => RoundingMode::HalfUp,
+        1 => RoundingMode::HalfEven,
+        _ => RoundingMode::TowardZero,
+    };
+    let rounded = adc_to_temp_x10_with_rounding(adc, mode);
+    assert!(domain.contains(&rounded));
+    assert!((rounded - temp_x10).abs() <= 1);
+
+    if let Some(sensor) = LinearSensor::new(
+        i32::from_le_bytes(field(data, 3)),
+        i32::from_le_bytes(field(data, 7)),
+        i16::from_le_bytes(field(data, 11)),
+        i16::from_le_bytes(field(data, 13)),
+    ) {
+        let uv = i32::from_le_bytes(field(data, 15));
+        let t = microvolts_to_temp_x10(uv, &sensor);
+        assert!((sensor.min_x10()..=sensor.max_x10()).contains(&t));
+        let _ = sensor.convert_checked(uv);
+    }
+
+    if let Ok(temps) = parse_adc_stream(data) {
+        assert!(temps.into_iter().all(|t| domain.contains(&t)));
+    }
+});
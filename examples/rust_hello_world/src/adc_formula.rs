@@ -0,0 +1,17 @@
+// ADC code -> 0.1°C arithmetic behind `adc_to_temp_x10`. `include!`d by both
+// `src/lib.rs` and `build.rs` (which generates the lookup table from it), so
+// the two cannot drift apart. Plain items, not a module.
+
+/// REQ_FUNC_001 arithmetic: `-400 + adc * 1650 / 4095`, rounded to nearest,
+/// for `adc` clamped to 12 bits.
+fn adc_formula_x10(adc_counts: u16) -> i16 {
+    let adc = adc_counts.min(4095);
+
+    // celsius = -40 + adc * (165 / 4095)
+    // x10: temp_x10 = -400 + adc * (1650 / 4095)
+    let numerator: i32 = (adc as i32) * 1650;
+    let scaled: i32 = (numerator + 2047) / 4095; // round
+    let temp_x10: i32 = -400 + scaled;
+
+    temp_x10.clamp(-400, 1250) as i16
+}
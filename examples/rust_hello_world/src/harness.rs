@@ -0,0 +1,2666 @@
+//! In-process TSIM test suite.
+//!
+//! The `junit_tests` binary runs [`run_suite`] and writes the JUnit report;
+//! other harnesses can call it directly and inspect the results.
+
+use std::cell::Cell;
+use std::env;
+use std::io::{self, Write};
+
+use crate::{
+    adc_to_temp_x10, adc_to_temp_x100, adc_to_temp_x10_clamped, adc_to_temp_x10_vref,
+    adc_to_temp_x10_with, adc_to_temp_x10_with_rounding, analyze_trace,
+    celsius_x10_to_fahrenheit_x10, convert_exact, error_x10, estimate_lag,
+    fahrenheit_x10_to_celsius_x10, format_temp, isqrt, max_conversion_error_x10,
+    microvolts_to_temp_x10, pack_temp, pack_temp_offset, parse_adc_stream, read, severity,
+    shared_magic, temp_x100_to_x10, temp_x10_to_adc, temp_x10_to_adc_checked, unpack_temp,
+    unpack_temp_offset, validate_trace, AdaptiveFilter, AdcStats, CachedConverter, Calibration,
+    ClampedSamplePolicy, ConfigError, Converter, DecodeError, DeltaLog, DomainError, DynFilter,
+    Filter, FilterChain, InverseVarianceFilter, LinearSensor, Measured, MonotonicTrend,
+    OscillationDetector, OutOfSpec, ParseError, PeakHold, Pipeline, PipelineConfig,
+    PlausibilityGate, PlausibilityLimits, REQ_MAP, RawFilter, Reading, RoundingMode,
+    STATE_MACHINE_RECORD_VERSION, SampleRecorder, SaturationMonitor, SaturationState, SensorBank,
+    Setpoint, SlidingExtrema, State, StateMachine, StateMachineBuilder, StateMachineConfig,
+    StepOutcome, TEMP_MAX_X10, TEMP_MIN_X10, TempX10, TempX100, ThermalModel, ThermistorTable,
+    ThermocoupleSensor, Threshold, TraceIssue, TraceIssueKind, TraceSummary, TransitionsExt,
+    TrimmedMeanFilter, TripEdge, VotingStateMachine,
+};
+
+// OSQAR-CODE-TRACE (test tags)
+//
+// TEST: TEST_CODE_001 TEST_VERIFY_001 TEST_METHOD_001 TEST_METHOD_002 TEST_METHOD_003 TEST_CONVERSION_001 TEST_FILTER_001 TEST_THRESHOLD_001 TEST_HYSTERESIS_001 TEST_END_TO_END_001 TEST_ERROR_RECOVERY_001 TEST_FAIL_SAFE_001 TEST_EXEC_001 TEST_REPORT_001
+
+/// Outcome of one harness test case.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// Thresholds and noise band the requirement tests check against.
+///
+/// The defaults match REQ_FUNC_003/004; other hardware grades can override
+/// them through `TSIM_HIGH_X10`, `TSIM_LOW_X10` and `TSIM_NOISE_BAND`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SuiteConfig {
+    pub high_x10: TempX10,
+    pub low_x10: TempX10,
+    /// Allowed deviation of the filtered output from the noise-free level.
+    pub noise_band: TempX10,
+}
+
+impl Default for SuiteConfig {
+    fn default() -> Self {
+        Self { high_x10: 1000, low_x10: 950, noise_band: 20 }
+    }
+}
+
+impl SuiteConfig {
+    /// Reads overrides from the process environment.
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// Applies the overrides `lookup` returns; unset keys keep the defaults.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let get = |key: &str, default: TempX10| match lookup(key) {
+            None => Ok(default),
+            Some(v) => v.trim().parse().map_err(|_| format!("{key}: invalid value {v:?}")),
+        };
+
+        let defaults = Self::default();
+        let config = Self {
+            high_x10: get("TSIM_HIGH_X10", defaults.high_x10)?,
+            low_x10: get("TSIM_LOW_X10", defaults.low_x10)?,
+            noise_band: get("TSIM_NOISE_BAND", defaults.noise_band)?,
+        };
+        if config.low_x10 >= config.high_x10 {
+            return Err(format!(
+                "TSIM_LOW_X10 ({}) must be below TSIM_HIGH_X10 ({})",
+                config.low_x10, config.high_x10
+            ));
+        }
+        if config.noise_band < 0 {
+            return Err(format!("TSIM_NOISE_BAND ({}) must not be negative", config.noise_band));
+        }
+        Ok(config)
+    }
+}
+
+fn pass(name: &'static str) -> TestResult {
+    TestResult { name, passed: true, message: String::new() }
+}
+
+fn fail(name: &'static str, message: impl Into<String>) -> TestResult {
+    TestResult { name, passed: false, message: message.into() }
+}
+
+fn test_conversion_full_range() -> TestResult {
+    // TEST_CONVERSION_001
+    let cases = [
+        (0u16, -400i16, 10i16),
+        (2048u16, 425i16, 10i16),
+        (4095u16, 1250i16, 10i16),
+    ];
+
+    for (adc, expected, tol) in cases {
+        let got = adc_to_temp_x10(adc);
+        let diff = got as i32 - expected as i32;
+        if diff > tol as i32 || diff < -(tol as i32) {
+            return fail(
+                "test_conversion_full_range",
+                format!("ADC {adc} => {got}, expected {expected}±{tol}"),
+            );
+        }
+    }
+
+    pass("test_conversion_full_range")
+}
+
+fn test_filter_noise_rejection(config: &SuiteConfig) -> TestResult {
+    // TEST_FILTER_001
+    let noisy: [i16; 8] = [500, 600, 450, 550, 500, 480, 520, 490];
+    let band = (500 - config.noise_band)..=(500 + config.noise_band);
+    let mut filter = Filter::new();
+
+    let mut outputs: Vec<i16> = Vec::new();
+    for s in noisy {
+        if let Some(out) = filter.update(s) {
+            outputs.push(out);
+        }
+    }
+
+    if outputs.is_empty() {
+        return fail("test_filter_noise_rejection", "Filter produced no outputs");
+    }
+
+    for o in outputs {
+        if !band.contains(&o) {
+            return fail("test_filter_noise_rejection", format!("Filtered output {o} out of expected band ({band:?})"));
+        }
+    }
+
+    pass("test_filter_noise_rejection")
+}
+
+fn test_threshold_and_hysteresis(config: &SuiteConfig) -> TestResult {
+    // TEST_THRESHOLD_001 + TEST_HYSTERESIS_001
+    let (high, low) = (config.high_x10, config.low_x10);
+    let mut sm = StateMachine::new(high, low);
+
+    if sm.state != State::Safe {
+        return fail("test_threshold_and_hysteresis", "Initial state must be SAFE");
+    }
+
+    if sm.evaluate(high - 1) != State::Safe {
+        return fail("test_threshold_and_hysteresis", format!("Must remain SAFE at {}", format_temp(high - 1, 1)));
+    }
+
+    if sm.evaluate(high) != State::Unsafe {
+        return fail("test_threshold_and_hysteresis", format!("Must transition to UNSAFE at {}", format_temp(high, 1)));
+    }
+
+    if sm.evaluate(low + 1) != State::Unsafe {
+        return fail("test_threshold_and_hysteresis", format!("Must remain UNSAFE at {} due to hysteresis", format_temp(low + 1, 1)));
+    }
+
+    if sm.evaluate(low) != State::Safe {
+        return fail("test_threshold_and_hysteresis", format!("Must recover to SAFE at {}", format_temp(low, 1)));
+    }
+
+    pass("test_threshold_and_hysteresis")
+}
+
+fn test_shared_magic_constant() -> TestResult {
+    let got = shared_magic();
+    if got != 42 {
+        return fail(
+            "test_shared_magic_constant",
+            format!("shared_magic() => {got}, expected 42"),
+        );
+    }
+
+    pass("test_shared_magic_constant")
+}
+
+fn test_estimate_lag_delayed_signal() -> TestResult {
+    // Pseudo-random input so the cross-correlation has a single clear peak.
+    let mut seed: u32 = 12345;
+    let raw: Vec<i16> = (0..64)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) % 400) as i16 + 300
+        })
+        .collect();
+
+    let delay = 3;
+    let mut delayed = vec![raw[0]; delay];
+    delayed.extend_from_slice(&raw[..raw.len() - delay]);
+
+    match estimate_lag(&raw, &delayed, 8) {
+        Some(3) => {}
+        other => {
+            return fail(
+                "test_estimate_lag_delayed_signal",
+                format!("estimate_lag => {other:?}, expected Some(3)"),
+            )
+        }
+    }
+
+    if estimate_lag(&raw, &delayed[..10], 8).is_some() {
+        return fail("test_estimate_lag_delayed_signal", "Mismatched lengths must return None");
+    }
+
+    pass("test_estimate_lag_delayed_signal")
+}
+
+fn test_filter_sum_saturates() -> TestResult {
+    let mut filter = Filter::new();
+    for _ in 0..5 {
+        filter.update(500);
+    }
+    filter.set_sum_for_test(1_000_000);
+    let saturated = filter.update(500);
+    if saturated != Some(i16::MAX) {
+        return fail(
+            "test_filter_sum_saturates",
+            format!("update => {saturated:?}, expected saturation to {}", i16::MAX),
+        );
+    }
+
+    // Averages outside the TSIM range are valid for other sensors: 140.0°C
+    // is inside the LM35's rating.
+    let lm35 = microvolts_to_temp_x10(1_400_000, &LinearSensor::LM35);
+    let mut filter = Filter::new();
+    let mut averaged = None;
+    for _ in 0..6 {
+        averaged = filter.update(lm35);
+    }
+    if averaged != Some(1400) {
+        return fail("test_filter_sum_saturates", format!("LM35 140.0°C averaged to {averaged:?}"));
+    }
+
+    pass("test_filter_sum_saturates")
+}
+
+fn test_filter_warmup_blend() -> TestResult {
+    let mut filter = Filter::<5>::with_warmup(200);
+
+    let outputs: Vec<Option<i16>> = (0..6).map(|_| filter.update(600)).collect();
+    let expected = [Some(280), Some(360), Some(440), Some(520), Some(600), Some(600)];
+    if outputs != expected {
+        return fail(
+            "test_filter_warmup_blend",
+            format!("warm-up outputs {outputs:?}, expected {expected:?}"),
+        );
+    }
+
+    filter.reset();
+    if filter.update(600) != Some(280) {
+        return fail("test_filter_warmup_blend", "Output after reset must restart near the fallback");
+    }
+
+    let mut wide = Filter::<4>::with_warmup(0);
+    let outputs: Vec<Option<i16>> = (0..4).map(|_| wide.update(400)).collect();
+    let expected = [Some(100), Some(200), Some(300), Some(400)];
+    if outputs != expected {
+        return fail(
+            "test_filter_warmup_blend",
+            format!("N=4 warm-up outputs {outputs:?}, expected {expected:?}"),
+        );
+    }
+
+    pass("test_filter_warmup_blend")
+}
+
+fn test_transitions_ramp() -> TestResult {
+    // 90.0C..=110.0C and back down in 2.0C steps.
+    let up = (900..=1100).step_by(20);
+    let down = (900..=1080).rev().step_by(20);
+    let ramp: Vec<i16> = up.chain(down).collect();
+
+    let events: Vec<(usize, State)> = ramp.into_iter().transitions(1000, 950).collect();
+
+    // Trip at 100.0C (index 5); recover at 94.0C on the way down (index 18).
+    let expected = [(5, State::Unsafe), (18, State::Safe)];
+    if events != expected {
+        return fail(
+            "test_transitions_ramp",
+            format!("transitions {events:?}, expected {expected:?}"),
+        );
+    }
+
+    pass("test_transitions_ramp")
+}
+
+fn test_pipeline_invalid_code() -> TestResult {
+    let mut pipeline = Pipeline::new(1000, 950).with_invalid_code(0xFFFF);
+
+    if pipeline.convert(0xFFFF).is_some() {
+        return fail("test_pipeline_invalid_code", "Sentinel 0xFFFF must not convert");
+    }
+    if pipeline.convert(0x0FFF) != Some(1250) {
+        return fail("test_pipeline_invalid_code", "0x0FFF must convert to 125.0C");
+    }
+
+    for _ in 0..6 {
+        pipeline.step(0x0FFF);
+    }
+    if pipeline.step(0xFFFF).is_some() || !pipeline.is_faulted() {
+        return fail("test_pipeline_invalid_code", "Sentinel must be reported as a fault");
+    }
+    pipeline.step(0x0FFF);
+    if pipeline.is_faulted() {
+        return fail("test_pipeline_invalid_code", "Fault must clear on the next valid code");
+    }
+
+    pass("test_pipeline_invalid_code")
+}
+
+fn test_sensor_bank_aggregate() -> TestResult {
+    let mut bank: SensorBank<3> = SensorBank::new(1000, 950);
+
+    // ADC 2048 ~= 42.5C, ADC 4095 = 125.0C.
+    for _ in 0..6 {
+        bank.step(0, 2048);
+        bank.step(1, 2048);
+        bank.step(2, 2048);
+    }
+    if bank.any_unsafe() {
+        return fail("test_sensor_bank_aggregate", "No channel should be UNSAFE at 42.5C");
+    }
+
+    for _ in 0..6 {
+        bank.step(0, 4095);
+        bank.step(2, 4095);
+    }
+    let states: Vec<State> = (0..3).map(|ch| bank.channel(ch).state()).collect();
+    if states != [State::Unsafe, State::Safe, State::Unsafe] {
+        return fail(
+            "test_sensor_bank_aggregate",
+            format!("channel states {states:?}, expected channels 0 and 2 UNSAFE"),
+        );
+    }
+    if !bank.any_unsafe() {
+        return fail("test_sensor_bank_aggregate", "Aggregate must report UNSAFE");
+    }
+
+    pass("test_sensor_bank_aggregate")
+}
+
+fn test_peak_hold() -> TestResult {
+    let mut hold = PeakHold::new();
+    if hold.peak().is_some() || hold.trough().is_some() {
+        return fail("test_peak_hold", "Fresh PeakHold must be empty");
+    }
+
+    for t in [250, 1010, -120, 600, 999] {
+        hold.observe(t);
+    }
+    if hold.peak() != Some(1010) || hold.trough() != Some(-120) {
+        return fail(
+            "test_peak_hold",
+            format!("peak/trough {:?}/{:?}, expected 1010/-120", hold.peak(), hold.trough()),
+        );
+    }
+
+    hold.reset();
+    if hold.peak().is_some() || hold.trough().is_some() {
+        return fail("test_peak_hold", "Reset must clear peak and trough");
+    }
+
+    pass("test_peak_hold")
+}
+
+fn test_state_machine_record_round_trip() -> TestResult {
+    let mut sm = StateMachine::new(1000, -50);
+    sm.evaluate(1200);
+
+    let bytes = sm.to_bytes();
+    if bytes[..6] != [STATE_MACHINE_RECORD_VERSION, 0xE8, 0x03, 0xCE, 0xFF, 0x01] {
+        return fail(
+            "test_state_machine_record_round_trip",
+            format!("to_bytes => {bytes:02X?}, unexpected layout"),
+        );
+    }
+
+    match StateMachine::from_bytes(&bytes) {
+        Ok(restored) if restored.to_bytes() == bytes && restored.state == State::Unsafe => {}
+        other => {
+            return fail(
+                "test_state_machine_record_round_trip",
+                format!("Round trip failed: state {:?}", other.map(|m| m.state)),
+            )
+        }
+    }
+
+    // A latched trip must come back latched, with every option intact.
+    let mut latched = StateMachine::builder(1000, 950)
+        .debounce(2)
+        .recovery_confirm(3)
+        .latch(true)
+        .trip_edge(TripEdge::Exclusive)
+        .build();
+    latched.evaluate(1100);
+    latched.evaluate(1100);
+    let Ok(mut restored) = StateMachine::from_bytes(&latched.to_bytes()) else {
+        return fail("test_state_machine_record_round_trip", "Configured record must decode");
+    };
+    if restored.config() != latched.config() || !restored.is_latched() || restored.transition_count() != 1 {
+        return fail("test_state_machine_record_round_trip", "Record must keep config, latch and counts");
+    }
+    for _ in 0..10 {
+        restored.evaluate(500);
+    }
+    if restored.state != State::Unsafe {
+        return fail("test_state_machine_record_round_trip", "Restored latch must hold Unsafe");
+    }
+
+    pass("test_state_machine_record_round_trip")
+}
+
+fn test_state_machine_record_rejects_garbage() -> TestResult {
+    let bytes = StateMachine::new(1000, 950).to_bytes();
+
+    if StateMachine::from_bytes(&bytes[..4]).err() != Some(DecodeError::Length(4)) {
+        return fail("test_state_machine_record_rejects_garbage", "Truncated record must be rejected");
+    }
+
+    let corrupt = |at: usize, value: u8| {
+        let mut garbage = bytes;
+        garbage[at] = value;
+        StateMachine::from_bytes(&garbage).err()
+    };
+    if corrupt(0, 0) != Some(DecodeError::Version(0)) {
+        return fail("test_state_machine_record_rejects_garbage", "Unknown version must be rejected");
+    }
+    // The earlier 5-byte layout carried no options and must not restore.
+    if StateMachine::from_bytes(&[0xE8, 0x03, 0xB6, 0x03, 0x01]).err() != Some(DecodeError::Length(5)) {
+        return fail("test_state_machine_record_rejects_garbage", "Legacy record must be rejected");
+    }
+    if corrupt(5, 0x7F) != Some(DecodeError::State(0x7F)) {
+        return fail("test_state_machine_record_rejects_garbage", "Unknown state byte must be rejected");
+    }
+    if corrupt(6, 2) != Some(DecodeError::Field(6)) || corrupt(15, 0x08) != Some(DecodeError::Field(15)) {
+        return fail("test_state_machine_record_rejects_garbage", "Invalid option bytes must be rejected");
+    }
+    let mut zero_debounce = bytes;
+    zero_debounce[7..11].fill(0);
+    if StateMachine::from_bytes(&zero_debounce).err() != Some(DecodeError::Field(7)) {
+        return fail("test_state_machine_record_rejects_garbage", "Zero debounce must be rejected");
+    }
+
+    pass("test_state_machine_record_rejects_garbage")
+}
+
+fn test_hysteresis_pct() -> TestResult {
+    for (pct, expected_low) in [(5u8, 950i16), (10, 900)] {
+        match StateMachine::from_high_and_hysteresis_pct(1000, pct) {
+            Ok(sm) if sm.high_x10() == 1000 && sm.low_x10() == expected_low => {}
+            Ok(sm) => {
+                return fail(
+                    "test_hysteresis_pct",
+                    format!("{pct}% => low {}, expected {expected_low}", sm.low_x10()),
+                )
+            }
+            Err(e) => return fail("test_hysteresis_pct", format!("{pct}% rejected: {e:?}")),
+        }
+    }
+
+    if StateMachine::from_high_and_hysteresis_pct(1000, 0).err() != Some(ConfigError::EmptyHysteresis) {
+        return fail("test_hysteresis_pct", "0% hysteresis must be rejected");
+    }
+    for (high, pct) in [(30000, 255u8), (1000, 150), (1000, 101)] {
+        if StateMachine::from_high_and_hysteresis_pct(high, pct).err() != Some(ConfigError::OutOfRange) {
+            return fail("test_hysteresis_pct", format!("{pct}% of {high} must be rejected"));
+        }
+    }
+    if StateMachine::from_high_and_hysteresis_pct(1000, 100).map(|sm| sm.low_x10()) != Ok(0) {
+        return fail("test_hysteresis_pct", "100% hysteresis must recover at 0.0°C");
+    }
+
+    pass("test_hysteresis_pct")
+}
+
+fn test_filter_update_tee() -> TestResult {
+    let mut filter = Filter::new();
+    let mut plain = Filter::new();
+
+    for s in [500, 600, 450, 550, 500, 480, 520] {
+        let (raw, filtered) = filter.update_tee(s);
+        if raw != s {
+            return fail("test_filter_update_tee", format!("tee echoed {raw}, expected {s}"));
+        }
+        if filtered != plain.update(s) {
+            return fail("test_filter_update_tee", "Filtered output must match update()");
+        }
+    }
+
+    pass("test_filter_update_tee")
+}
+
+fn test_pipeline_rate_per_second() -> TestResult {
+    let mut pipeline = Pipeline::new(1300, 1250).with_sample_period_ms(250);
+
+    // 273 ADC counts is exactly 11.0C, so the ramp (and its average) rises 110 x10 per sample.
+    let mut rates = Vec::new();
+    for i in 0..12u16 {
+        pipeline.step(i * 273);
+        rates.push(pipeline.rate_per_second_x10());
+    }
+
+    if rates[5].is_some() {
+        return fail("test_pipeline_rate_per_second", "Rate needs two filtered outputs");
+    }
+    // 110 x10 per 250ms => 440 x10/s (44.0C/s).
+    for rate in &rates[6..] {
+        if *rate != Some(440) {
+            return fail(
+                "test_pipeline_rate_per_second",
+                format!("rate {rate:?}, expected Some(440)"),
+            );
+        }
+    }
+
+    if Pipeline::new(1000, 950).rate_per_second_x10().is_some() {
+        return fail("test_pipeline_rate_per_second", "No period configured must yield None");
+    }
+
+    pass("test_pipeline_rate_per_second")
+}
+
+fn test_filter_chain_matches_manual() -> TestResult {
+    let mut chain = FilterChain::new().stage(Filter::new()).stage(Filter::new());
+    let mut first = Filter::new();
+    let mut second = Filter::new();
+
+    let samples = [500, 600, 450, 550, 500, 480, 520, 490, 510, 530, 470, 505];
+    for s in samples {
+        let manual = first.update(s).and_then(|t| second.update(t));
+        let chained = chain.update(s);
+        if chained != manual {
+            return fail(
+                "test_filter_chain_matches_manual",
+                format!("chain {chained:?} != manual {manual:?} for sample {s}"),
+            );
+        }
+    }
+
+    pass("test_filter_chain_matches_manual")
+}
+
+fn test_plausibility_gate_rejects_spike() -> TestResult {
+    let mut gate = PlausibilityGate::new(50);
+    let ramp = [500, 510, 520, 930, 530, 540];
+
+    let accepted: Vec<Option<i16>> = ramp.iter().map(|&t| gate.accept(t)).collect();
+    let expected = [Some(500), Some(510), Some(520), None, Some(530), Some(540)];
+    if accepted != expected {
+        return fail(
+            "test_plausibility_gate_rejects_spike",
+            format!("accepted {accepted:?}, expected {expected:?}"),
+        );
+    }
+
+    pass("test_plausibility_gate_rejects_spike")
+}
+
+fn test_conversion_rounding_modes() -> TestResult {
+    // ADC 2 => 3300 / 4095 = 0.806 => rounds to 1 (nearest) or 0 (truncated).
+    let cases = [
+        (RoundingMode::HalfUp, -399i16),
+        (RoundingMode::HalfEven, -399),
+        (RoundingMode::TowardZero, -400),
+    ];
+    for (mode, expected) in cases {
+        let got = adc_to_temp_x10_with_rounding(2, mode);
+        if got != expected {
+            return fail(
+                "test_conversion_rounding_modes",
+                format!("ADC 2 with {mode:?} => {got}, expected {expected}"),
+            );
+        }
+    }
+
+    for adc in 0..=4095u16 {
+        if adc_to_temp_x10_with_rounding(adc, RoundingMode::HalfUp) != adc_to_temp_x10(adc) {
+            return fail(
+                "test_conversion_rounding_modes",
+                format!("HalfUp differs from adc_to_temp_x10 at ADC {adc}"),
+            );
+        }
+    }
+
+    pass("test_conversion_rounding_modes")
+}
+
+fn test_pipeline_step_fail_safe() -> TestResult {
+    let mut pipeline = Pipeline::new(1000, 950).with_fail_safe_x10(1200);
+
+    // ADC 1638 => 26.0C.
+    for i in 0..5 {
+        if pipeline.step_fail_safe(1638) != (1200, State::Unsafe) {
+            return fail(
+                "test_pipeline_step_fail_safe",
+                format!("Step {i}: priming must report the fail-safe pair"),
+            );
+        }
+    }
+
+    let ready = pipeline.step_fail_safe(1638);
+    if ready != (260, State::Safe) {
+        return fail(
+            "test_pipeline_step_fail_safe",
+            format!("Primed output {ready:?}, expected (260, Safe)"),
+        );
+    }
+
+    pass("test_pipeline_step_fail_safe")
+}
+
+fn test_clone_is_independent() -> TestResult {
+    let mut filter = Filter::new();
+    let sm = StateMachine::new(1000, 950);
+    for _ in 0..5 {
+        filter.update(500);
+    }
+
+    let mut filter_branch = filter.clone();
+    let mut sm_branch = sm.clone();
+    for _ in 0..5 {
+        filter_branch.update(1200);
+    }
+    sm_branch.evaluate(1200);
+
+    if sm.state != State::Safe || sm_branch.state != State::Unsafe {
+        return fail("test_clone_is_independent", "Advancing the clone changed the original state machine");
+    }
+    if filter.update(500) != Some(500) || filter_branch.update(1200) != Some(1200) {
+        return fail("test_clone_is_independent", "Advancing the clone changed the original filter");
+    }
+
+    pass("test_clone_is_independent")
+}
+
+fn test_filter_last_sample() -> TestResult {
+    let mut filter = Filter::new();
+    if filter.last_sample().is_some() {
+        return fail("test_filter_last_sample", "Fresh filter must have no last sample");
+    }
+
+    for s in [500, 600, 450, 550, 500, 480, 520] {
+        filter.update(s);
+        if filter.last_sample() != Some(s) {
+            return fail(
+                "test_filter_last_sample",
+                format!("last_sample {:?}, expected Some({s})", filter.last_sample()),
+            );
+        }
+    }
+
+    pass("test_filter_last_sample")
+}
+
+fn test_microvolts_lm35() -> TestResult {
+    let lm35 = LinearSensor::LM35;
+    let cases = [
+        (0i32, 0i16),          // 0.0C
+        (1_000_000, 1000),     // 100.0C
+        (255_500, 256),        // 25.55C rounds half away from zero
+        (255_499, 255),
+        (-5_500, -6),          // -0.55C
+        (2_000_000, 1500),     // clamped to rated maximum
+    ];
+
+    for (uv, expected) in cases {
+        let got = microvolts_to_temp_x10(uv, &lm35);
+        if got != expected {
+            return fail(
+                "test_microvolts_lm35",
+                format!("{uv} uV => {got}, expected {expected}"),
+            );
+        }
+    }
+
+    if LinearSensor::new(0, 0, -400, 1250).is_some() {
+        return fail("test_microvolts_lm35", "Zero slope must be rejected");
+    }
+
+    pass("test_microvolts_lm35")
+}
+
+fn test_dyn_filter_matches_static() -> TestResult {
+    let samples = [500, 600, 450, 550, 500, 480, 520, 490, 510, 530, 470, 505];
+
+    let mut dyn3 = DynFilter::<16>::new(3).expect("window 3 fits");
+    let mut static3 = Filter::<3>::default();
+    let mut dyn7 = DynFilter::<16>::new(7).expect("window 7 fits");
+    let mut static7 = Filter::<7>::default();
+
+    for s in samples {
+        if dyn3.update(s) != static3.update(s) || dyn7.update(s) != static7.update(s) {
+            return fail(
+                "test_dyn_filter_matches_static",
+                format!("DynFilter diverged from Filter<N> at sample {s}"),
+            );
+        }
+    }
+
+    if DynFilter::<16>::new(0).is_some() || DynFilter::<16>::new(17).is_some() {
+        return fail("test_dyn_filter_matches_static", "Window 0 and window > MAX must be rejected");
+    }
+
+    pass("test_dyn_filter_matches_static")
+}
+
+fn test_write_markdown() -> TestResult {
+    let results = [pass("case_ok"), fail("case_bad", "got 1 | expected 2")];
+    let mut out = Vec::new();
+    if write_markdown(&mut out, "suite", &SuiteConfig::default(), &results).is_err() {
+        return fail("test_write_markdown", "write_markdown returned an error");
+    }
+    let text = String::from_utf8(out).unwrap_or_default();
+
+    let expected_lines = [
+        "Configuration: `TSIM_HIGH_X10=1000`, `TSIM_LOW_X10=950`, `TSIM_NOISE_BAND=20`",
+        "| Name | Result | Message |",
+        "| case_ok | ✅ |  |",
+        "| case_bad | ❌ | got 1 \\| expected 2 |",
+        "**1 passed, 1 failed**",
+    ];
+    for line in expected_lines {
+        if !text.lines().any(|l| l == line) {
+            return fail("test_write_markdown", format!("Missing line {line:?} in:\n{text}"));
+        }
+    }
+
+    pass("test_write_markdown")
+}
+
+fn test_pipeline_filter_bypass() -> TestResult {
+    let mut pipeline = Pipeline::new(1000, 950);
+
+    for _ in 0..6 {
+        pipeline.step(1638); // 26.0C
+    }
+
+    pipeline.set_filtering(false);
+    // A single 125.0C sample trips immediately without averaging.
+    if pipeline.step(4095) != Some(State::Unsafe) {
+        return fail("test_pipeline_filter_bypass", "Raw mode must respond on the first sample");
+    }
+    if pipeline.step(1638) != Some(State::Safe) {
+        return fail("test_pipeline_filter_bypass", "Raw mode must recover on the first sample");
+    }
+
+    pipeline.set_filtering(true);
+    for i in 0..5 {
+        if pipeline.step(1638).is_some() {
+            return fail(
+                "test_pipeline_filter_bypass",
+                format!("Step {i} after re-enable: filter must re-prime from empty"),
+            );
+        }
+    }
+    if pipeline.step(1638) != Some(State::Safe) {
+        return fail("test_pipeline_filter_bypass", "Filter must produce output once re-primed");
+    }
+
+    pass("test_pipeline_filter_bypass")
+}
+
+fn test_pipeline_effective_trip() -> TestResult {
+    let pipeline = Pipeline::new(1000, 950);
+
+    // N = 5 lags a ramp by 2 samples: 100.0C + 2 * 1.0C = 102.0C.
+    if pipeline.effective_trip_x10(10) != 1020 {
+        return fail(
+            "test_pipeline_effective_trip",
+            format!("effective trip {}, expected 1020", pipeline.effective_trip_x10(10)),
+        );
+    }
+
+    // Cross-check by driving the filter and state machine with the same ramp.
+    let mut filter = Filter::new();
+    let mut sm = StateMachine::new(1000, 950);
+    let mut tripped_at = None;
+    for k in 0..100i16 {
+        let input = 900 + k * 10;
+        if let Some(filtered) = filter.update(input) {
+            if sm.evaluate(filtered) == State::Unsafe {
+                tripped_at = Some(input);
+                break;
+            }
+        }
+    }
+    if tripped_at != Some(1020) {
+        return fail(
+            "test_pipeline_effective_trip",
+            format!("simulated trip at input {tripped_at:?}, expected Some(1020)"),
+        );
+    }
+
+    pass("test_pipeline_effective_trip")
+}
+
+fn test_try_evaluate_domain() -> TestResult {
+    let mut sm = StateMachine::new(1000, 950);
+
+    if sm.try_evaluate(1100) != Ok(State::Unsafe) {
+        return fail("test_try_evaluate_domain", "In-range 110.0C must evaluate to UNSAFE");
+    }
+    if sm.try_evaluate(5000) != Err(DomainError(5000)) {
+        return fail("test_try_evaluate_domain", "5000 is outside the sensor domain");
+    }
+    if sm.try_evaluate(-401) != Err(DomainError(-401)) {
+        return fail("test_try_evaluate_domain", "-401 is outside the sensor domain");
+    }
+    if sm.state != State::Unsafe {
+        return fail("test_try_evaluate_domain", "Rejected inputs must not change state");
+    }
+
+    pass("test_try_evaluate_domain")
+}
+
+fn test_read_variants() -> TestResult {
+    let cases = [
+        (2048u16, Reading::Ok(425)),
+        (1, Reading::Ok(-400)),
+        (0, Reading::StuckLow),
+        (4095, Reading::StuckHigh),
+        (4096, Reading::OutOfRange),
+        (0xFFFF, Reading::OutOfRange),
+    ];
+
+    for (adc, expected) in cases {
+        let got = read(adc);
+        if got != expected {
+            return fail("test_read_variants", format!("read({adc}) => {got:?}, expected {expected:?}"));
+        }
+    }
+
+    pass("test_read_variants")
+}
+
+fn test_format_temp() -> TestResult {
+    let cases = [
+        (-400i16, 1u8, "-40.0"),
+        (-400, 0, "-40"),
+        (0, 1, "0.0"),
+        (0, 0, "0"),
+        (1234, 1, "123.4"),
+        (1234, 0, "123"),
+        (125, 0, "13"),
+        (-125, 0, "-13"),
+        (-5, 1, "-0.5"),
+        (-4, 0, "0"),
+    ];
+
+    for (temp, decimals, expected) in cases {
+        let got = format_temp(temp, decimals).to_string();
+        if got != expected {
+            return fail(
+                "test_format_temp",
+                format!("format_temp({temp}, {decimals}) => {got:?}, expected {expected:?}"),
+            );
+        }
+    }
+
+    pass("test_format_temp")
+}
+
+fn test_filter_ac_rms() -> TestResult {
+    let mut filter = Filter::new();
+    // Sinusoid-like ripple around 50.0C: deviations 0, +10, 0, -10, 0.
+    for s in [500, 510, 500, 490] {
+        filter.update(s);
+    }
+    if filter.ac_rms_x10().is_some() {
+        return fail("test_filter_ac_rms", "RMS must be None until the window is full");
+    }
+    filter.update(500);
+
+    // sqrt((0 + 100 + 0 + 100 + 0) / 5) = sqrt(40) = 6.32 => 6.
+    if filter.ac_rms_x10() != Some(6) {
+        return fail(
+            "test_filter_ac_rms",
+            format!("ac_rms_x10 => {:?}, expected Some(6)", filter.ac_rms_x10()),
+        );
+    }
+
+    for (n, expected) in [(0u64, 0u64), (1, 1), (15, 3), (16, 4), (u64::MAX, 4_294_967_295)] {
+        if isqrt(n) != expected {
+            return fail("test_filter_ac_rms", format!("isqrt({n}) => {}, expected {expected}", isqrt(n)));
+        }
+    }
+
+    pass("test_filter_ac_rms")
+}
+
+fn test_voting_truth_tables() -> TestResult {
+    const S: State = State::Safe;
+    const U: State = State::Unsafe;
+
+    let pairs = [(S, S), (S, U), (U, S), (U, U)];
+    let one_oo_two = [S, U, U, U];
+    let two_oo_two = [S, S, S, U];
+    for (i, &(a, b)) in pairs.iter().enumerate() {
+        if State::or(a, b) != one_oo_two[i] || State::and(a, b) != two_oo_two[i] {
+            return fail("test_voting_truth_tables", format!("1oo2/2oo2 wrong for {a:?}, {b:?}"));
+        }
+    }
+
+    let Some(two_oo_three) = VotingStateMachine::<3>::new(2) else {
+        return fail("test_voting_truth_tables", "2oo3 must be constructible");
+    };
+    for mask in 0..8u8 {
+        let inputs = [0, 1, 2].map(|bit| if mask & (1 << bit) != 0 { U } else { S });
+        let expected = if mask.count_ones() >= 2 { U } else { S };
+        if two_oo_three.vote(&inputs) != expected {
+            return fail("test_voting_truth_tables", format!("2oo3 wrong for {inputs:?}"));
+        }
+    }
+
+    if VotingStateMachine::<3>::new(0).is_some() || VotingStateMachine::<3>::new(4).is_some() {
+        return fail("test_voting_truth_tables", "M must be within 1..=K");
+    }
+
+    pass("test_voting_truth_tables")
+}
+
+fn test_calibration_large_gain() -> TestResult {
+    // Gain 2.0 expressed with terms whose product overflows i32.
+    let Some(cal) = Calibration::new(2_000_000_000, 1_000_000_000, 0) else {
+        return fail("test_calibration_large_gain", "Calibration must be constructible");
+    };
+
+    let cases = [(600i16, 1200i16), (1240, 1250), (-390, -400), (0, 0)];
+    for (input, expected) in cases {
+        let got = cal.apply(input);
+        if got != expected {
+            return fail(
+                "test_calibration_large_gain",
+                format!("apply({input}) => {got}, expected {expected}"),
+            );
+        }
+    }
+
+    // Two-point: measured 19.0C/81.0C should read 20.0C/80.0C.
+    let Some(two_point) = Calibration::two_point(190, 200, 810, 800) else {
+        return fail("test_calibration_large_gain", "Two-point calibration must be constructible");
+    };
+    if two_point.apply(190) != 200 || two_point.apply(810) != 800 || two_point.apply(500) != 500 {
+        return fail("test_calibration_large_gain", "Two-point calibration must hit its reference points");
+    }
+
+    if Calibration::new(1, 0, 0).is_some() {
+        return fail("test_calibration_large_gain", "Zero denominator must be rejected");
+    }
+
+    pass("test_calibration_large_gain")
+}
+
+fn test_adc_stats() -> TestResult {
+    let mut stats = AdcStats::new();
+    if stats.mean().is_some() || stats.min().is_some() || stats.max().is_some() {
+        return fail("test_adc_stats", "Empty statistics must report None");
+    }
+
+    for adc in [2040, 2050, 2047, 2049, 2045, 2052] {
+        stats.observe(adc);
+    }
+
+    // Sum 12283 / 6 = 2047.17 => 2047.
+    if stats.count() != 6 || stats.mean() != Some(2047) {
+        return fail("test_adc_stats", format!("mean {:?}, expected Some(2047)", stats.mean()));
+    }
+    if stats.min() != Some(2040) || stats.max() != Some(2052) {
+        return fail("test_adc_stats", format!("min/max {:?}/{:?}, expected 2040/2052", stats.min(), stats.max()));
+    }
+
+    // Long-running monitoring: the count saturates instead of wrapping.
+    let mut stats = AdcStats { count: u32::MAX - 1, sum: 2000 * (u32::MAX as u64 - 1), min: Some(2000), max: Some(2000) };
+    for adc in [2000, 4000, 100] {
+        stats.observe(adc);
+    }
+    if stats.count() != u32::MAX || stats.mean() != Some(2000) || stats.min() != Some(100) || stats.max() != Some(4000) {
+        return fail("test_adc_stats", "Statistics must saturate at u32::MAX samples");
+    }
+
+    pass("test_adc_stats")
+}
+
+fn test_pipeline_snapshot_restore() -> TestResult {
+    let mut pipeline = Pipeline::new(1000, 950);
+    let trace = [2048u16, 2100, 2200, 3600, 3700, 3800, 3900, 4000, 2500, 2400, 2300, 2200];
+
+    for &adc in &trace[..4] {
+        pipeline.step(adc);
+    }
+    let snap = pipeline.snapshot();
+    let mut reference = pipeline.clone();
+
+    // Diverge, then roll back.
+    for _ in 0..10 {
+        pipeline.step(4095);
+    }
+    pipeline.restore(&snap);
+
+    for &adc in &trace[4..] {
+        let got = pipeline.step(adc);
+        let expected = reference.step(adc);
+        if got != expected {
+            return fail(
+                "test_pipeline_snapshot_restore",
+                format!("After restore, ADC {adc} => {got:?}, expected {expected:?}"),
+            );
+        }
+    }
+
+    pass("test_pipeline_snapshot_restore")
+}
+
+fn test_thermocouple_cold_junction() -> TestResult {
+    // 1 uV per count, no offset: the ADC code is the thermocouple EMF in uV.
+    let tc = ThermocoupleSensor::type_k(0, 1000);
+
+    // Cold junction at 25.0C (E = 1.000 mV). Reference EMFs per NIST type K:
+    // 100C = 4.096 mV, 300C = 12.209 mV, 37C = 1.490 mV.
+    let cases = [(0u16, 250i16), (3096, 1000), (11209, 3000), (490, 370)];
+    for (adc, expected) in cases {
+        let got = tc.hot_junction_x10(adc, 250);
+        if (got - expected).abs() > 10 {
+            return fail(
+                "test_thermocouple_cold_junction",
+                format!("EMF {adc} uV at CJ 25.0C => {got}, expected {expected}+-10"),
+            );
+        }
+    }
+
+    // Same hot junction seen from a 0C cold junction needs the full EMF.
+    if (tc.hot_junction_x10(4096, 0) - 1000).abs() > 10 {
+        return fail("test_thermocouple_cold_junction", "100C at CJ 0C must read 100.0C");
+    }
+
+    pass("test_thermocouple_cold_junction")
+}
+
+fn test_oscillation_detector() -> TestResult {
+    // Four transitions within 20 cycles counts as flapping.
+    let mut flapping = OscillationDetector::<4>::new(20);
+    let fired: Vec<bool> = [100, 104, 109, 113].iter().map(|&c| flapping.record_transition(c)).collect();
+    if fired != [false, false, false, true] {
+        return fail("test_oscillation_detector", format!("flapping fired {fired:?}, expected only on the 4th"));
+    }
+
+    let mut clean = OscillationDetector::<4>::new(20);
+    if clean.record_transition(100) {
+        return fail("test_oscillation_detector", "A single clean transition must not fire");
+    }
+    // Slow, well-separated transitions never fire either.
+    for c in [200, 300, 400, 500] {
+        if clean.record_transition(c) {
+            return fail("test_oscillation_detector", format!("Slow transition at {c} must not fire"));
+        }
+    }
+
+    pass("test_oscillation_detector")
+}
+
+fn test_linear_sensor_usable_range() -> TestResult {
+    let Some(sensor) = LinearSensor::LM35.with_usable_range(0, 1100) else {
+        return fail("test_linear_sensor_usable_range", "0.0..=110.0C must be accepted");
+    };
+
+    let cases = [
+        (250_000i32, Ok(250i16)),          // 25.0C in spec
+        (-50_000, Err(OutOfSpec(-50))),    // -5.0C below spec
+        (1_200_000, Err(OutOfSpec(1200))), // 120.0C above spec, but electrically valid
+    ];
+    for (uv, expected) in cases {
+        let got = sensor.convert_checked(uv);
+        if got != expected {
+            return fail(
+                "test_linear_sensor_usable_range",
+                format!("{uv} uV => {got:?}, expected {expected:?}"),
+            );
+        }
+    }
+
+    if LinearSensor::LM35.convert_checked(1_200_000) != Ok(1200) {
+        return fail("test_linear_sensor_usable_range", "Without a usable range only clamping applies");
+    }
+
+    if LinearSensor::LM35.with_usable_range(1100, 0).is_some() {
+        return fail("test_linear_sensor_usable_range", "An inverted usable range must be rejected");
+    }
+
+    pass("test_linear_sensor_usable_range")
+}
+
+fn test_sample_recorder_wraparound() -> TestResult {
+    let mut recorder = SampleRecorder::<4>::new();
+    for adc in [10, 20, 30] {
+        recorder.push(adc);
+    }
+    let partial: Vec<u16> = recorder.dump().collect();
+    if partial != [10, 20, 30] {
+        return fail("test_sample_recorder_wraparound", format!("partial dump {partial:?}"));
+    }
+
+    for adc in [40, 50, 60, 70] {
+        recorder.push(adc);
+    }
+    let full: Vec<u16> = recorder.dump().collect();
+    if full != [40, 50, 60, 70] {
+        return fail(
+            "test_sample_recorder_wraparound",
+            format!("dump {full:?}, expected the 4 most recent oldest-first"),
+        );
+    }
+
+    pass("test_sample_recorder_wraparound")
+}
+
+fn test_threshold_presets() -> TestResult {
+    let cases = [
+        ("tsim_default", StateMachine::tsim_default(), 1000i16, 950i16),
+        ("li_ion_charge", StateMachine::li_ion_charge(), 450, 420),
+        ("li_ion_discharge", StateMachine::li_ion_discharge(), 600, 570),
+    ];
+
+    for (name, sm, high, low) in cases {
+        if sm.high_x10() != high || sm.low_x10() != low || sm.state != State::Safe {
+            return fail(
+                "test_threshold_presets",
+                format!("{name}: {}/{}, expected {high}/{low}", sm.high_x10(), sm.low_x10()),
+            );
+        }
+    }
+
+    pass("test_threshold_presets")
+}
+
+fn test_filter_settling_samples() -> TestResult {
+    // N=5 at 90%: 4 samples give 80% of the step, so 5 are needed.
+    let filter = Filter::new();
+    if filter.settling_samples(90) != 5 {
+        return fail("test_filter_settling_samples", format!("got {}", filter.settling_samples(90)));
+    }
+    if filter.settling_samples(60) != 3 || filter.settling_samples(0) != 0 {
+        return fail("test_filter_settling_samples", "unexpected value at 60% or 0%");
+    }
+
+    pass("test_filter_settling_samples")
+}
+
+fn test_suite_config_overrides() -> TestResult {
+    let lookup = |vars: &'static [(&'static str, &'static str)]| {
+        move |key: &str| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+    };
+
+    if SuiteConfig::from_lookup(lookup(&[])) != Ok(SuiteConfig::default()) {
+        return fail("test_suite_config_overrides", "Unset variables must keep the defaults");
+    }
+
+    let overridden = SuiteConfig::from_lookup(lookup(&[
+        ("TSIM_HIGH_X10", "850"),
+        ("TSIM_LOW_X10", "800"),
+        ("TSIM_NOISE_BAND", "35"),
+    ]));
+    if overridden != Ok(SuiteConfig { high_x10: 850, low_x10: 800, noise_band: 35 }) {
+        return fail("test_suite_config_overrides", format!("Unexpected config {overridden:?}"));
+    }
+
+    // Lowering only the trip point below the default recovery is rejected, as is garbage.
+    if SuiteConfig::from_lookup(lookup(&[("TSIM_HIGH_X10", "850")])).is_ok() {
+        return fail("test_suite_config_overrides", "Accepted low >= high");
+    }
+    if SuiteConfig::from_lookup(lookup(&[("TSIM_NOISE_BAND", "wide")])).is_ok() {
+        return fail("test_suite_config_overrides", "Accepted a non-numeric value");
+    }
+
+    // The requirement tests follow the overridden thresholds.
+    if let Ok(config) = overridden {
+        let results = [test_filter_noise_rejection(&config), test_threshold_and_hysteresis(&config)];
+        if let Some(r) = results.iter().find(|r| !r.passed) {
+            return fail("test_suite_config_overrides", format!("{} failed: {}", r.name, r.message));
+        }
+    }
+
+    pass("test_suite_config_overrides")
+}
+
+fn test_delta_log_round_trip() -> TestResult {
+    // Slow drift with small noise, plus one full-scale jump.
+    let mut trace: Vec<u16> = (0..200u16).map(|i| 1800 + i / 4 + (i * 7) % 3).collect();
+    trace.extend([4095, 0]);
+
+    let mut buf = [0u8; 512];
+    let mut log = DeltaLog::new(&mut buf);
+    for &code in &trace {
+        if log.encode(code).is_empty() {
+            return fail("test_delta_log_round_trip", "Buffer unexpectedly full");
+        }
+    }
+
+    let decoded: Vec<u16> = DeltaLog::decode(log.as_bytes()).collect();
+    if decoded != trace {
+        return fail("test_delta_log_round_trip", "Decoded trace differs from input");
+    }
+
+    let encoded = log.as_bytes().len();
+    let raw = trace.len() * 2;
+    if encoded * 3 > raw * 2 {
+        return fail("test_delta_log_round_trip", format!("{encoded} bytes is not below 2/3 of raw {raw}"));
+    }
+
+    // A sample that does not fit is refused without corrupting the log.
+    let mut small = [0u8; 3];
+    let mut log = DeltaLog::new(&mut small);
+    let _ = log.encode(1000);
+    let _ = log.encode(1001);
+    if !log.encode(3000).is_empty() || DeltaLog::decode(log.as_bytes()).collect::<Vec<_>>() != [1000, 1001] {
+        return fail("test_delta_log_round_trip", "Overflowing sample must be dropped");
+    }
+
+    pass("test_delta_log_round_trip")
+}
+
+fn test_filter_noise_reduction() -> TestResult {
+    let got = (
+        Filter::<4>::noise_reduction_x100(),
+        Filter::<9>::noise_reduction_x100(),
+        Filter::<5>::noise_reduction_x100(),
+    );
+    if got != (200, 300, 223) {
+        return fail("test_filter_noise_reduction", format!("got {got:?}, expected (200, 300, 223)"));
+    }
+    if Filter::<4>::dc_gain_x1000() != 1000 || Filter::<9>::dc_gain_x1000() != 1000 {
+        return fail("test_filter_noise_reduction", "DC gain must be 1000");
+    }
+
+    pass("test_filter_noise_reduction")
+}
+
+fn test_trip_edge_at_threshold() -> TestResult {
+    let mut inclusive = StateMachine::new(1000, 950);
+    let mut exclusive = StateMachine::new(1000, 950).with_trip_edge(TripEdge::Exclusive);
+
+    if inclusive.trip_edge() != TripEdge::Inclusive {
+        return fail("test_trip_edge_at_threshold", "Default must be inclusive");
+    }
+    if inclusive.evaluate(1000) != State::Unsafe {
+        return fail("test_trip_edge_at_threshold", "Inclusive must trip at exactly 100.0C");
+    }
+    if exclusive.evaluate(1000) != State::Safe {
+        return fail("test_trip_edge_at_threshold", "Exclusive must stay SAFE at exactly 100.0C");
+    }
+    if exclusive.evaluate(1001) != State::Unsafe {
+        return fail("test_trip_edge_at_threshold", "Exclusive must trip at 100.1C");
+    }
+    if exclusive.evaluate(950) != State::Safe {
+        return fail("test_trip_edge_at_threshold", "Recovery at 95.0C must stay inclusive");
+    }
+
+    pass("test_trip_edge_at_threshold")
+}
+
+fn test_filter_trend_slope() -> TestResult {
+    // Ramp of +0.3C per sample; the window wraps several times.
+    let mut filter = Filter::new();
+    for i in 0..12 {
+        if i == 4 && filter.trend_slope_x100().is_some() {
+            return fail("test_filter_trend_slope", "Slope must be None before the window is full");
+        }
+        let _ = filter.update(200 + 3 * i);
+    }
+    if filter.trend_slope_x100() != Some(30) {
+        return fail("test_filter_trend_slope", format!("Ramp slope {:?}, expected Some(30)", filter.trend_slope_x100()));
+    }
+
+    let mut flat = Filter::<8>::default();
+    for _ in 0..8 {
+        let _ = flat.update(-125);
+    }
+    if flat.trend_slope_x100() != Some(0) {
+        return fail("test_filter_trend_slope", format!("Flat slope {:?}, expected Some(0)", flat.trend_slope_x100()));
+    }
+
+    pass("test_filter_trend_slope")
+}
+
+fn test_adaptive_filter() -> TestResult {
+    let mut filter = AdaptiveFilter::<2, 16>::new(50);
+
+    // Noise of +/-3.0C with period 3: the short average swings 48.5..51.5C,
+    // the output must stay on the long average.
+    let noise = [470, 530, 500];
+    for i in 0..60 {
+        let out = filter.update(noise[i % 3]);
+        if i >= 17 && !matches!(out, Some(497..=503)) {
+            return fail("test_adaptive_filter", format!("Noise not smoothed at sample {i}: {out:?}"));
+        }
+    }
+
+    // A 30.0C step is tracked by the short window within three samples.
+    let mut out = None;
+    for _ in 0..3 {
+        out = filter.update(800);
+    }
+    if out != Some(800) {
+        return fail("test_adaptive_filter", format!("Step not tracked: {out:?}, expected Some(800)"));
+    }
+
+    pass("test_adaptive_filter")
+}
+
+fn test_req_map_unique() -> TestResult {
+    // Item existence is checked when the crate compiles.
+    if REQ_MAP.is_empty() {
+        return fail("test_req_map_unique", "REQ_MAP is empty");
+    }
+    for (i, (id, item)) in REQ_MAP.iter().enumerate() {
+        if !id.starts_with("REQ_") || item.is_empty() {
+            return fail("test_req_map_unique", format!("Malformed entry ({id:?}, {item:?})"));
+        }
+        if REQ_MAP[..i].iter().any(|(other, _)| other == id) {
+            return fail("test_req_map_unique", format!("Duplicate requirement ID {id}"));
+        }
+    }
+
+    pass("test_req_map_unique")
+}
+
+fn test_thermal_model_junction() -> TestResult {
+    // 45.5 C/W, e.g. a small QFN on a 2-layer board.
+    let model = ThermalModel { theta_ja_x1000: 45_500 };
+
+    if model.junction_temp_x10(250, 0) != 250 {
+        return fail("test_thermal_model_junction", "Zero power must give junction == ambient");
+    }
+    // 1.2 W * 45.5 C/W = 54.6 C rise.
+    let tj = model.junction_temp_x10(250, 1200);
+    if tj != 796 {
+        return fail("test_thermal_model_junction", format!("got {tj}, expected 796"));
+    }
+    if model.junction_temp_x10(1000, u32::MAX) != TempX10::MAX {
+        return fail("test_thermal_model_junction", "Huge power must saturate");
+    }
+
+    pass("test_thermal_model_junction")
+}
+
+fn test_transition_count() -> TestResult {
+    let mut sm = StateMachine::new(1000, 950);
+    for t in [1000, 1010, 950, 990, 1000, 900, 800] {
+        sm.evaluate(t);
+    }
+    if sm.transition_count() != 4 {
+        return fail("test_transition_count", format!("got {}, expected 4", sm.transition_count()));
+    }
+
+    sm.reset_transition_count();
+    if sm.transition_count() != 0 || sm.state != State::Safe {
+        return fail("test_transition_count", "Reset must zero the count and keep the state");
+    }
+
+    pass("test_transition_count")
+}
+
+fn test_filter_update_missing() -> TestResult {
+    let mut filter = Filter::new();
+    if filter.update_missing().is_some() || filter.last_sample().is_some() {
+        return fail("test_filter_update_missing", "Missing sample before any data must be a no-op");
+    }
+
+    // Missing slots hold the previous sample: 100, 200, [200], 300, [300], 400.
+    let mut out = None;
+    for s in [Some(100), Some(200), None, Some(300), None, Some(400)] {
+        out = match s {
+            Some(s) => filter.update(s),
+            None => filter.update_missing(),
+        };
+    }
+    // Window after six slots: 200, 200, 300, 300, 400.
+    if out != Some(280) {
+        return fail("test_filter_update_missing", format!("got {out:?}, expected Some(280)"));
+    }
+
+    pass("test_filter_update_missing")
+}
+
+fn test_threshold_domain() -> TestResult {
+    if Threshold::new(1000).map(Threshold::get) != Some(1000)
+        || Threshold::new(TEMP_MIN_X10).is_none()
+        || Threshold::new(TEMP_MAX_X10).is_none()
+    {
+        return fail("test_threshold_domain", "In-range thresholds must be accepted");
+    }
+    if Threshold::new(-401).is_some() {
+        return fail("test_threshold_domain", "Below -40.0C must be rejected");
+    }
+    if Threshold::new(1251).is_some() {
+        return fail("test_threshold_domain", "Above 125.0C must be rejected");
+    }
+
+    const HIGH: Threshold = Threshold::new_unchecked(1000);
+    const LOW: Threshold = Threshold::new_unchecked(950);
+    let sm = StateMachine::from_thresholds(HIGH, LOW);
+    if sm.high_x10() != 1000 || sm.low_x10() != 950 {
+        return fail("test_threshold_domain", "from_thresholds must keep the values");
+    }
+
+    pass("test_threshold_domain")
+}
+
+fn test_filter_group_delay() -> TestResult {
+    let odd = Filter::new().group_delay_samples();
+    let even = Filter::<4>::default().group_delay_samples();
+    if (odd, even) != (2, 1) {
+        return fail("test_filter_group_delay", format!("got ({odd}, {even}), expected (2, 1)"));
+    }
+
+    pass("test_filter_group_delay")
+}
+
+fn test_parse_adc_stream() -> TestResult {
+    let bytes = [0x00, 0x00, 0x00, 0x08, 0xff, 0x0f];
+    let temps: Vec<TempX10> = match parse_adc_stream(&bytes) {
+        Ok(temps) => temps.collect(),
+        Err(e) => return fail("test_parse_adc_stream", format!("Rejected valid capture: {e:?}")),
+    };
+    let expected = [0, 2048, 4095].map(adc_to_temp_x10);
+    if temps != expected {
+        return fail("test_parse_adc_stream", format!("got {temps:?}, expected {expected:?}"));
+    }
+
+    if parse_adc_stream(&bytes[..5]).err() != Some(ParseError::OddLength(5)) {
+        return fail("test_parse_adc_stream", "Odd-length capture must be rejected");
+    }
+
+    pass("test_parse_adc_stream")
+}
+
+fn test_debounce_and_latch() -> TestResult {
+    let mut sm = StateMachine::builder(1000, 950).debounce(3).latch(true).build();
+
+    // Two over-threshold samples, then one below: the count restarts.
+    for t in [1005, 1010, 990] {
+        if sm.evaluate(t) != State::Safe {
+            return fail("test_debounce_and_latch", format!("Tripped early at {t}"));
+        }
+    }
+    for (i, t) in [1005, 1010, 1020].into_iter().enumerate() {
+        let expected = if i == 2 { State::Unsafe } else { State::Safe };
+        if sm.evaluate(t) != expected {
+            return fail("test_debounce_and_latch", format!("Sample {i} of the run: expected {expected:?}"));
+        }
+    }
+
+    // Latched through recovery.
+    for t in [950, 900, 800] {
+        if sm.evaluate(t) != State::Unsafe || !sm.is_latched() {
+            return fail("test_debounce_and_latch", format!("Latch released at {t}"));
+        }
+    }
+
+    sm.clear_latch();
+    if sm.evaluate(900) != State::Safe || sm.is_latched() {
+        return fail("test_debounce_and_latch", "Must recover once the latch is cleared");
+    }
+
+    pass("test_debounce_and_latch")
+}
+
+fn test_filter_raw_sum() -> TestResult {
+    let mut a = Filter::new();
+    let mut b = Filter::new();
+    let mut out = None;
+    for s in [500, 510, 520, 530, 540, 550] {
+        out = a.update(s);
+        let _ = b.update(s + 100);
+    }
+    if a.sample_count() != 5 || Some((a.raw_sum() / a.sample_count() as i32) as TempX10) != out {
+        return fail("test_filter_raw_sum", format!("{} / {} does not match {out:?}", a.raw_sum(), a.sample_count()));
+    }
+
+    let global = (a.raw_sum() + b.raw_sum()) / (a.sample_count() + b.sample_count()) as i32;
+    if global != 580 {
+        return fail("test_filter_raw_sum", format!("Global average {global}, expected 580"));
+    }
+
+    pass("test_filter_raw_sum")
+}
+
+fn test_margin_to_trip() -> TestResult {
+    let mut sm = StateMachine::new(1000, 950);
+    if sm.margin_to_trip_x10(968) != 32 || sm.margin_to_trip_x10(1004) != -4 {
+        return fail("test_margin_to_trip", "Safe margin must be high - filtered");
+    }
+
+    sm.evaluate(1010);
+    if sm.margin_to_trip_x10(1010) != 60 || sm.margin_to_trip_x10(940) != -10 {
+        return fail("test_margin_to_trip", "Unsafe margin must be filtered - low");
+    }
+
+    pass("test_margin_to_trip")
+}
+
+fn test_cached_converter() -> TestResult {
+    struct Counting {
+        sensor: LinearSensor,
+        calls: Cell<u32>,
+    }
+    impl Converter for Counting {
+        fn convert(&self, raw: i32) -> TempX10 {
+            self.calls.set(self.calls.get() + 1);
+            self.sensor.convert(raw)
+        }
+    }
+
+    let mut cached = CachedConverter::new(Counting { sensor: LinearSensor::LM35, calls: Cell::new(0) });
+    let first = cached.convert(250_000);
+    let again = cached.convert(250_000);
+    if (first, again) != (250, 250) || cached.inner().calls.get() != 1 {
+        return fail("test_cached_converter", "Repeated reading must come from the cache");
+    }
+
+    if cached.convert(251_000) != 251 || cached.inner().calls.get() != 2 {
+        return fail("test_cached_converter", "Changed reading must be reconverted");
+    }
+
+    pass("test_cached_converter")
+}
+
+fn test_setpoint_error() -> TestResult {
+    // Mixing the two types is rejected at compile time (doctests on `error_x10`).
+    if error_x10(Setpoint(600), Measured(552)) != 48 || error_x10(Setpoint(600), Measured(615)) != -15 {
+        return fail("test_setpoint_error", "Error must be setpoint - measured");
+    }
+    if error_x10(Setpoint(i16::MAX), Measured(i16::MIN)) != i16::MAX {
+        return fail("test_setpoint_error", "Error must saturate");
+    }
+
+    pass("test_setpoint_error")
+}
+
+fn test_filter_next_to_evict() -> TestResult {
+    let mut filter = Filter::new();
+    let samples: Vec<TempX10> = (0..12).map(|i| 300 + 7 * i).collect();
+    for (i, &s) in samples.iter().enumerate() {
+        let expected = (i >= 5).then(|| samples[i - 5]);
+        if filter.next_to_evict() != expected {
+            return fail("test_filter_next_to_evict", format!("Before sample {i}: {:?}, expected {expected:?}", filter.next_to_evict()));
+        }
+        let _ = filter.update(s);
+    }
+
+    pass("test_filter_next_to_evict")
+}
+
+fn test_pipeline_degraded_mode() -> TestResult {
+    const SENTINEL: u16 = 0xFFFF;
+    let hot = 3600; // 105.0C
+    let mut p = Pipeline::new(1000, 950).with_invalid_code(SENTINEL).with_degrade_after(3);
+
+    // Two valid samples between sentinels never fill the filter.
+    for round in 1..=3 {
+        for adc in [hot, hot] {
+            if p.step(adc).is_some() {
+                return fail("test_pipeline_degraded_mode", "Filter must still be priming");
+            }
+        }
+        let _ = p.step(SENTINEL);
+        if p.is_degraded() != (round == 3) {
+            return fail("test_pipeline_degraded_mode", format!("Wrong degraded flag after {round} resets"));
+        }
+    }
+
+    // Raw pass-through while the filter primes again, then back to normal.
+    for i in 0..4 {
+        if p.step_outcome(hot) != Some(StepOutcome { state: State::Unsafe, degraded: true, just_primed: false }) {
+            return fail("test_pipeline_degraded_mode", format!("Sample {i} must be evaluated raw"));
+        }
+    }
+    let _ = p.step(hot);
+    if p.step_outcome(hot) != Some(StepOutcome { state: State::Unsafe, degraded: false, just_primed: true }) || p.is_degraded() {
+        return fail("test_pipeline_degraded_mode", "First filtered output must end degraded mode");
+    }
+
+    pass("test_pipeline_degraded_mode")
+}
+
+fn test_from_setpoint_band() -> TestResult {
+    let cases = [(600, 40, 620, 580), (600, 45, 623, 578), (0, 1, 1, 0)];
+    for (setpoint, band, high, low) in cases {
+        match StateMachine::from_setpoint_band(setpoint, band) {
+            Ok(sm) if sm.high_x10() == high && sm.low_x10() == low => {}
+            _ => return fail("test_from_setpoint_band", format!("{setpoint}/{band}: expected {high}/{low}")),
+        }
+    }
+
+    for band in [0, -10] {
+        if StateMachine::from_setpoint_band(600, band).err() != Some(ConfigError::EmptyHysteresis) {
+            return fail("test_from_setpoint_band", format!("Band {band} must be rejected"));
+        }
+    }
+    if StateMachine::from_setpoint_band(i16::MAX, 10).err() != Some(ConfigError::OutOfRange) {
+        return fail("test_from_setpoint_band", "Overflowing trip point must be rejected");
+    }
+
+    pass("test_from_setpoint_band")
+}
+
+fn test_analyze_trace() -> TestResult {
+    // Multiples of 273 counts convert exactly: 1911 -> 37.0C, 3549 -> 103.0C.
+    let mut trace = vec![1911u16; 5];
+    trace.extend([3549; 7]);
+    trace.extend([1911; 8]);
+
+    // Filtered from sample 5: 50.2, 63.4, 76.6, 89.8, 103.0 x3 (Unsafe), 89.8 (Safe), ...
+    let expected = TraceSummary {
+        transitions: 2,
+        peak_filtered_x10: Some(1030),
+        unsafe_samples: 3,
+        final_state: State::Safe,
+    };
+    let summary = analyze_trace(&trace, &PipelineConfig::default());
+    if summary != expected {
+        return fail("test_analyze_trace", format!("got {summary:?}, expected {expected:?}"));
+    }
+
+    pass("test_analyze_trace")
+}
+
+fn test_recovery_confirmation() -> TestResult {
+    let mut sm = StateMachine::builder(1000, 950).recovery_confirm(2).build();
+    sm.evaluate(1010);
+
+    // Qualifies, then rises again: the wait restarts.
+    for t in [950, 940, 960] {
+        if sm.evaluate(t) != State::Unsafe {
+            return fail("test_recovery_confirmation", format!("Recovered early at {t}"));
+        }
+    }
+    // First qualifying sample plus exactly two more.
+    for (i, t) in [940, 930, 920].into_iter().enumerate() {
+        let expected = if i == 2 { State::Safe } else { State::Unsafe };
+        if sm.evaluate(t) != expected {
+            return fail("test_recovery_confirmation", format!("Sample {i} of the wait: expected {expected:?}"));
+        }
+    }
+
+    pass("test_recovery_confirmation")
+}
+
+fn test_filter_sample_at() -> TestResult {
+    let mut filter = Filter::new();
+    for s in [10, 20, 30] {
+        let _ = filter.update(s);
+    }
+    if filter.sample_at(0) != filter.last_sample() || filter.sample_at(2) != Some(10) || filter.sample_at(3).is_some() {
+        return fail("test_filter_sample_at", "Wrong ages while priming");
+    }
+
+    for s in [40, 50, 60, 70] {
+        let _ = filter.update(s);
+    }
+    let count = filter.sample_count();
+    if filter.sample_at(0) != filter.last_sample() || filter.sample_at(count - 1) != Some(30) || filter.sample_at(count).is_some() {
+        return fail("test_filter_sample_at", "Wrong ages after wrap-around");
+    }
+    if filter.samples().collect::<Vec<_>>() != [30, 40, 50, 60, 70] {
+        return fail("test_filter_sample_at", "samples() must be oldest first");
+    }
+
+    pass("test_filter_sample_at")
+}
+
+fn test_trimmed_mean_filter() -> TestResult {
+    let mut filter = TrimmedMeanFilter::<5>::new();
+    let mut out = None;
+    for s in [400, 500, 510, 1200, -300, 520] {
+        out = filter.update(s);
+    }
+    // Window 500, 510, 1200, -300, 520: both spikes dropped.
+    if out != Some(510) {
+        return fail("test_trimmed_mean_filter", format!("got {out:?}, expected Some(510)"));
+    }
+
+    pass("test_trimmed_mean_filter")
+}
+
+fn test_is_chatter_safe() -> TestResult {
+    let sm = StateMachine::new(1000, 950);
+    if !sm.is_chatter_safe(15) {
+        return fail("test_is_chatter_safe", "5.0C band must be safe against 1.5C noise");
+    }
+    if sm.is_chatter_safe(25) || sm.is_chatter_safe(40) {
+        return fail("test_is_chatter_safe", "5.0C band must not be safe against 2.5C noise or more");
+    }
+
+    pass("test_is_chatter_safe")
+}
+
+fn test_convert_exact() -> TestResult {
+    let adc: [u16; 8] = [0, 1, 273, 1024, 2048, 3549, 4094, 4095];
+    let temps = convert_exact(&adc);
+    for (code, temp) in adc.iter().zip(temps) {
+        if temp != adc_to_temp_x10(*code) {
+            return fail("test_convert_exact", format!("Code {code}: {temp} differs from adc_to_temp_x10"));
+        }
+    }
+
+    pass("test_convert_exact")
+}
+
+fn test_pipeline_warm_start() -> TestResult {
+    let mut warm = Pipeline::warm_start(425, 1000, 950);
+    if warm.step(2048) != Some(State::Safe) {
+        return fail("test_pipeline_warm_start", "First step after a warm start must yield a state");
+    }
+    // Output was available from construction, so nothing is "just" primed.
+    let mut warm = Pipeline::warm_start(425, 1000, 950);
+    if warm.step_outcome(2048).map(|o| o.just_primed) != Some(false) {
+        return fail("test_pipeline_warm_start", "Warm start must count as primed");
+    }
+    let mut warm_raw = Pipeline::warm_start(425, 1000, 950).with_raw_filtering();
+    if warm_raw.step_outcome(2048).map(|o| (o.state, o.just_primed)) != Some((State::Safe, false)) {
+        return fail("test_pipeline_warm_start", "Warm start must prime the raw filter too");
+    }
+
+    let hot = Pipeline::warm_start(1100, 1000, 950);
+    if hot.state() != State::Unsafe {
+        return fail("test_pipeline_warm_start", "Hot warm start must trip immediately");
+    }
+
+    let mut cold = Pipeline::new(1000, 950);
+    if cold.step(2048).is_some() {
+        return fail("test_pipeline_warm_start", "Cold start must still prime");
+    }
+
+    pass("test_pipeline_warm_start")
+}
+
+fn test_filter_seed_from() -> TestResult {
+    let mut a = Filter::new();
+    for s in [600, 610, 620, 630, 640] {
+        let _ = a.update(s);
+    }
+    let mut b = Filter::new();
+    let _ = b.update(100);
+
+    b.seed_from(&a);
+    if b.current() != a.current() || b.current() != Some(620) {
+        return fail("test_filter_seed_from", format!("Seeded {:?}, source {:?}", b.current(), a.current()));
+    }
+    // B's first own reading replaces the oldest inherited sample.
+    if b.update(650) != Some(630) {
+        return fail("test_filter_seed_from", "Seeded filter must continue the source timeline");
+    }
+
+    pass("test_filter_seed_from")
+}
+
+fn test_temp_to_adc_checked() -> TestResult {
+    if temp_x10_to_adc_checked(425) != (2048, false) {
+        return fail("test_temp_to_adc_checked", format!("42.5C: {:?}", temp_x10_to_adc_checked(425)));
+    }
+    if temp_x10_to_adc_checked(1300) != (4095, true) || temp_x10_to_adc_checked(-500) != (0, true) {
+        return fail("test_temp_to_adc_checked", "Out-of-range temperatures must clamp and be flagged");
+    }
+    if let Some(t) = (TEMP_MIN_X10..=TEMP_MAX_X10).find(|&t| adc_to_temp_x10(temp_x10_to_adc(t)) != t) {
+        return fail("test_temp_to_adc_checked", format!("Round trip fails at {t}"));
+    }
+
+    pass("test_temp_to_adc_checked")
+}
+
+fn test_conversion_x100_resolution() -> TestResult {
+    if adc_to_temp_x100(0) != -4000 || adc_to_temp_x100(4095) != 12_500 {
+        return fail("test_conversion_x100_resolution", "x100 end points must be -40.00C and 125.00C");
+    }
+    // 2047 and 2048 share a 0.1C value but not a 0.01C one.
+    let (a, b) = (adc_to_temp_x100(2047), adc_to_temp_x100(2048));
+    if adc_to_temp_x10(2047) != adc_to_temp_x10(2048) || a == b {
+        return fail("test_conversion_x100_resolution", format!("x100 {a} / {b} not finer than x10"));
+    }
+    // Narrowing rounds twice, so it may differ from the direct x10 value by one step.
+    if let Some(adc) = (0..=4095u16).find(|&adc| (temp_x100_to_x10(adc_to_temp_x100(adc)) - adc_to_temp_x10(adc)).abs() > 1) {
+        return fail("test_conversion_x100_resolution", format!("Narrowed x100 off by more than 0.1C at code {adc}"));
+    }
+
+    pass("test_conversion_x100_resolution")
+}
+
+fn test_monotonic_trend() -> TestResult {
+    let mut trend = MonotonicTrend::<4>::new();
+    for t in [500, 510, 520] {
+        trend.push(t);
+    }
+    if trend.is_rising() {
+        return fail("test_monotonic_trend", "Must not report before K samples");
+    }
+    trend.push(530);
+    trend.push(545);
+    if !trend.is_rising() || trend.is_falling() {
+        return fail("test_monotonic_trend", "Clean ramp must be rising");
+    }
+
+    let mut flat = MonotonicTrend::<4>::new();
+    for t in [500, 503, 498, 502, 499, 501] {
+        flat.push(t);
+        if flat.is_rising() || flat.is_falling() {
+            return fail("test_monotonic_trend", format!("Noisy flat input reported a trend at {t}"));
+        }
+    }
+
+    pass("test_monotonic_trend")
+}
+
+fn test_transition_table() -> TestResult {
+    let table = StateMachine::new(1000, 950).transition_table();
+    let expected = [
+        (State::Safe, "filtered >= 1000".to_string(), State::Unsafe),
+        (State::Unsafe, "filtered <= 950".to_string(), State::Safe),
+    ];
+    if table != expected {
+        return fail("test_transition_table", format!("got {table:?}"));
+    }
+
+    let options = StateMachine::builder(1000, 950).trip_edge(TripEdge::Exclusive).debounce(3).latch(true).build();
+    if options.transition_table()[0].1 != "filtered > 1000 for 3 consecutive samples"
+        || options.transition_table()[1].1 != "filtered <= 950 after clear_latch"
+    {
+        return fail("test_transition_table", format!("got {:?}", options.transition_table()));
+    }
+
+    pass("test_transition_table")
+}
+
+fn test_raw_filter_before_conversion() -> TestResult {
+    // Strongly bent characteristic: 0.4C per count below code 1000, 0.2C above.
+    const POINTS: [(i32, i32); 4] = [(0, -400), (1000, 0), (2000, 200), (4095, 600)];
+    let table = match ThermistorTable::new(&POINTS) {
+        Some(table) => table,
+        None => return fail("test_raw_filter_before_conversion", "Valid table rejected"),
+    };
+    if ThermistorTable::new(&[(10, 0), (10, 5)]).is_some() {
+        return fail("test_raw_filter_before_conversion", "Non-ascending table accepted");
+    }
+
+    let mut raw = RawFilter::<5>::new();
+    let mut converted = Filter::new();
+    let (mut before, mut after) = (None, None);
+    for code in [900, 1100, 900, 1100, 900, 1100] {
+        before = raw.update(code).map(|mean| table.convert(mean));
+        after = converted.update(table.convert(code));
+    }
+    // Mean code 1020 -> 0.4C, but mean of (2.0, -4.0, 2.0, -4.0, 2.0) -> -0.4C.
+    if (before, after) != (Some(4), Some(-4)) {
+        return fail("test_raw_filter_before_conversion", format!("before {before:?}, after {after:?}"));
+    }
+
+    // Through the pipeline: the same table, filtered before and after conversion.
+    let run = |pipeline: Pipeline| {
+        let mut p = pipeline.with_converter(table);
+        for code in [900, 1100, 900, 1100, 900, 1100] {
+            let _ = p.step(code);
+        }
+        p.last_filtered_x10()
+    };
+    let before = run(Pipeline::new(1000, 950).with_raw_filtering());
+    let after = run(Pipeline::new(1000, 950));
+    if (before, after) != (Some(4), Some(-4)) {
+        return fail("test_raw_filter_before_conversion", format!("Pipeline before {before:?}, after {after:?}"));
+    }
+
+    let mut p = Pipeline::new(1000, 950).with_raw_filtering();
+    let mut state = None;
+    for code in [3549, 3550, 3549, 3550, 3549, 3550] {
+        state = p.step(code);
+    }
+    if state != Some(State::Unsafe) {
+        return fail("test_raw_filter_before_conversion", "Raw-filtered pipeline must evaluate the converted mean");
+    }
+
+    pass("test_raw_filter_before_conversion")
+}
+
+fn test_state_machine_band() -> TestResult {
+    let sm = StateMachine::new(1000, 950);
+    if sm.band() != (950..=1000) {
+        return fail("test_state_machine_band", format!("band {:?}, expected 950..=1000", sm.band()));
+    }
+    if !sm.contains_in_band(950) || !sm.contains_in_band(1000) || sm.contains_in_band(949) || sm.contains_in_band(1001) {
+        return fail("test_state_machine_band", "Band must include both thresholds and nothing outside");
+    }
+
+    pass("test_state_machine_band")
+}
+
+fn test_filter_update_fractional() -> TestResult {
+    for samples in [[500, 501, 503, 507, 510, 512], [-3, -4, -2, -8, -1, -5]] {
+        let mut filter = Filter::new();
+        let mut out = None;
+        for s in samples {
+            out = filter.update_fractional(s);
+        }
+        let Some((whole, remainder)) = out else {
+            return fail("test_filter_update_fractional", "No output after a full window");
+        };
+        let n = filter.window_len() as i32;
+        if whole as i32 * n + remainder as i32 != filter.raw_sum() || remainder as i32 >= n {
+            return fail("test_filter_update_fractional", format!("{whole} + {remainder}/{n} != {}/{n}", filter.raw_sum()));
+        }
+    }
+
+    pass("test_filter_update_fractional")
+}
+
+fn test_sensor_bank_calibration() -> TestResult {
+    let (Some(plus), Some(minus)) = (Calibration::new(1, 1, 15), Calibration::new(1, 1, -20)) else {
+        return fail("test_sensor_bank_calibration", "Valid calibration rejected");
+    };
+    let mut bank = SensorBank::<3>::new(1000, 950);
+    bank.set_calibration(0, plus);
+    bank.set_calibration(1, minus);
+
+    for _ in 0..6 {
+        for ch in 0..3 {
+            let _ = bank.step(ch, 2048);
+        }
+    }
+    let temps: Vec<_> = (0..3).map(|ch| bank.channel(ch).last_filtered_x10()).collect();
+    if temps != [Some(440), Some(405), Some(425)] {
+        return fail("test_sensor_bank_calibration", format!("got {temps:?}"));
+    }
+
+    pass("test_sensor_bank_calibration")
+}
+
+fn test_pipeline_just_primed() -> TestResult {
+    let mut p = Pipeline::new(1000, 950).with_invalid_code(0xFFFF);
+    let flags = |p: &mut Pipeline, n: usize| -> Vec<Option<bool>> {
+        (0..n).map(|_| p.step_outcome(2048).map(|o| o.just_primed)).collect()
+    };
+
+    let expected = [None, None, None, None, None, Some(true), Some(false), Some(false)];
+    if flags(&mut p, 8) != expected {
+        return fail("test_pipeline_just_primed", "Flag must be set on the first output only");
+    }
+
+    // A sentinel discards the window; the next first output flags again.
+    let _ = p.step(0xFFFF);
+    if flags(&mut p, 8) != expected {
+        return fail("test_pipeline_just_primed", "Flag must be set again after re-priming");
+    }
+
+    pass("test_pipeline_just_primed")
+}
+
+fn test_conversion_worst_case_error() -> TestResult {
+    let worst = max_conversion_error_x10();
+    if worst > 1 {
+        return fail("test_conversion_worst_case_error", format!("Worst-case error {worst} exceeds 1 LSB"));
+    }
+    pass("test_conversion_worst_case_error")
+}
+
+fn test_conversion_vref_compensation() -> TestResult {
+    for adc in [0u16, 1000, 2048, 4095] {
+        if adc_to_temp_x10_vref(adc, 1200, 1200) != adc_to_temp_x10(adc) {
+            return fail("test_conversion_vref_compensation", "Nominal Vref must leave the reading uncorrected");
+        }
+    }
+
+    // Bandgap reads 1% low: the reference is 1% high, so the code scales up.
+    let corrected = adc_to_temp_x10_vref(2000, 1188, 1200);
+    if corrected != adc_to_temp_x10(2020) || corrected <= adc_to_temp_x10(2000) {
+        return fail("test_conversion_vref_compensation", "Low measured Vref must raise the result");
+    }
+
+    if adc_to_temp_x10_vref(4000, 600, 1200) != 1250 || adc_to_temp_x10_vref(2000, 0, 1200) != adc_to_temp_x10(2000) {
+        return fail("test_conversion_vref_compensation", "Corrected code must saturate and ignore a zero Vref");
+    }
+
+    pass("test_conversion_vref_compensation")
+}
+
+fn test_state_machine_within_sensor() -> TestResult {
+    // LM35 reports at most 150.0°C.
+    let sensor = LinearSensor::LM35;
+    if StateMachine::within_sensor(&sensor, 1600, 1500).err() != Some(ConfigError::OutOfRange) {
+        return fail("test_state_machine_within_sensor", "Unreachable trip point must be rejected");
+    }
+    if StateMachine::within_sensor(&sensor, 1000, -600).err() != Some(ConfigError::OutOfRange) {
+        return fail("test_state_machine_within_sensor", "Unreachable recovery point must be rejected");
+    }
+    if StateMachine::within_sensor(&sensor, 950, 1000).err() != Some(ConfigError::EmptyHysteresis) {
+        return fail("test_state_machine_within_sensor", "Empty hysteresis must be rejected");
+    }
+
+    match StateMachine::within_sensor(&sensor, 1500, 950) {
+        Ok(sm) if sm.high_x10() == 1500 && sm.low_x10() == 950 => {}
+        _ => return fail("test_state_machine_within_sensor", "In-range config must be accepted"),
+    }
+
+    pass("test_state_machine_within_sensor")
+}
+
+fn test_state_machine_config() -> TestResult {
+    let mut a = StateMachine::builder(1000, 950).debounce(3).latch(true).build();
+    let b = StateMachine::builder(1000, 950).debounce(3).latch(true).build();
+    for _ in 0..3 {
+        a.evaluate(1100);
+    }
+    if a.state == b.state || a.config() != b.config() {
+        return fail("test_state_machine_config", "Config must ignore runtime state");
+    }
+
+    let expected = StateMachineConfig {
+        high_x10: 1000,
+        low_x10: 950,
+        trip_edge: TripEdge::Inclusive,
+        debounce: 3,
+        recovery_confirm: 0,
+        latching: true,
+    };
+    if a.config() != expected || StateMachine::new(1000, 950).config() == expected {
+        return fail("test_state_machine_config", "Config must reflect every option");
+    }
+
+    pass("test_state_machine_config")
+}
+
+fn test_sliding_extrema_matches_brute_force() -> TestResult {
+    let mut seed: u32 = 2024;
+    let samples: Vec<TempX10> = (0..1000)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) % 1650) as TempX10 - 400
+        })
+        .collect();
+
+    let mut extrema = SlidingExtrema::<100>::new();
+    if extrema.min().is_some() || extrema.max().is_some() {
+        return fail("test_sliding_extrema_matches_brute_force", "Empty window must have no extrema");
+    }
+    for (i, &sample) in samples.iter().enumerate() {
+        extrema.push(sample);
+        let window = &samples[(i + 1).saturating_sub(100)..=i];
+        if extrema.min() != window.iter().copied().min() || extrema.max() != window.iter().copied().max() {
+            return fail("test_sliding_extrema_matches_brute_force", format!("Mismatch after sample {i}"));
+        }
+    }
+
+    pass("test_sliding_extrema_matches_brute_force")
+}
+
+fn test_fahrenheit_conversion() -> TestResult {
+    let cases = [(-400, -400), (0, 320), (370, 986), (1000, 2120), (1250, 2570)];
+    for (c, f) in cases {
+        if celsius_x10_to_fahrenheit_x10(c) != f || fahrenheit_x10_to_celsius_x10(f) != c {
+            return fail("test_fahrenheit_conversion", format!("{c} x0.1°C <-> {f} x0.1°F"));
+        }
+    }
+    // 0.1°C is 0.18°F, so Celsius survives a round trip through Fahrenheit.
+    for c in TEMP_MIN_X10..=TEMP_MAX_X10 {
+        if fahrenheit_x10_to_celsius_x10(celsius_x10_to_fahrenheit_x10(c)) != c {
+            return fail("test_fahrenheit_conversion", format!("Round trip changed {c}"));
+        }
+    }
+    if celsius_x10_to_fahrenheit_x10(TempX10::MAX) != i16::MAX {
+        return fail("test_fahrenheit_conversion", "Fahrenheit must saturate");
+    }
+    pass("test_fahrenheit_conversion")
+}
+
+fn test_state_machine_from_fahrenheit() -> TestResult {
+    match StateMachine::from_fahrenheit(2120, 2030) {
+        Ok(sm) if sm.high_x10() == 1000 && sm.low_x10() == 950 => {}
+        _ => return fail("test_state_machine_from_fahrenheit", "212°F/203°F must map to 100.0°C/95.0°C"),
+    }
+    // 1000.1°F and 1000.0°F both round to 537.8°C.
+    if StateMachine::from_fahrenheit(10001, 10000).err() != Some(ConfigError::EmptyHysteresis)
+        || StateMachine::from_fahrenheit(2030, 2120).is_ok()
+    {
+        return fail("test_state_machine_from_fahrenheit", "Ordering must be checked after conversion");
+    }
+    pass("test_state_machine_from_fahrenheit")
+}
+
+fn test_state_machine_frozen() -> TestResult {
+    let mut sm = StateMachine::new(1000, 950);
+    sm.set_frozen(true);
+    if !sm.is_frozen() || sm.evaluate(1200) != State::Safe || sm.transition_count() != 0 {
+        return fail("test_state_machine_frozen", "Frozen machine must ignore an over-threshold input");
+    }
+
+    sm.set_frozen(false);
+    if sm.evaluate(1200) != State::Unsafe {
+        return fail("test_state_machine_frozen", "Unfrozen machine must trip normally");
+    }
+
+    // Freezing holds whatever state the machine is in.
+    sm.set_frozen(true);
+    if sm.evaluate(900) != State::Unsafe {
+        return fail("test_state_machine_frozen", "Frozen machine must not recover");
+    }
+
+    pass("test_state_machine_frozen")
+}
+
+#[cfg(tsim_generated_lut)]
+fn test_generated_lut_matches_conversion() -> TestResult {
+    for adc in 0..=4095u16 {
+        if crate::ADC_TO_TEMP_X10_LUT[adc as usize] != adc_to_temp_x10(adc) {
+            return fail("test_generated_lut_matches_conversion", format!("LUT differs at code {adc}"));
+        }
+    }
+    pass("test_generated_lut_matches_conversion")
+}
+
+fn test_saturation_monitor() -> TestResult {
+    let mut monitor = SaturationMonitor::new(3);
+    let spike = [2048, 4095, 2048].map(|adc| monitor.update(adc));
+    if spike != [SaturationState::Normal, SaturationState::Transient, SaturationState::Normal] {
+        return fail("test_saturation_monitor", "A single rail reading must not fault");
+    }
+
+    let run = [0, 0, 0, 0].map(|adc| monitor.update(adc));
+    let expected = [
+        SaturationState::Transient,
+        SaturationState::Transient,
+        SaturationState::StuckLow,
+        SaturationState::StuckLow,
+    ];
+    if run != expected {
+        return fail("test_saturation_monitor", "A sustained rail run must fault");
+    }
+
+    // Switching rails restarts the count.
+    if monitor.update(4095) != SaturationState::Transient {
+        return fail("test_saturation_monitor", "Opposite rail must start a new run");
+    }
+
+    pass("test_saturation_monitor")
+}
+
+fn test_reading_from_u16() -> TestResult {
+    for adc in [2048u16, 0, 4095] {
+        let reading: Reading = adc.into();
+        if reading != read(adc) {
+            return fail("test_reading_from_u16", format!("into() differs from read() for {adc}"));
+        }
+    }
+    pass("test_reading_from_u16")
+}
+
+fn test_pipeline_clamped_policy() -> TestResult {
+    if adc_to_temp_x10_clamped(0xFFFF) != (1250, true) || adc_to_temp_x10_clamped(4095) != (1250, false) {
+        return fail("test_pipeline_clamped_policy", "Only codes above 4095 are clamped");
+    }
+
+    let run = |policy| {
+        let mut p = Pipeline::new(1000, 950).with_clamped_policy(policy);
+        for _ in 0..6 {
+            let _ = p.step(2048);
+        }
+        let _ = p.step(0xFFFF);
+        p.last_filtered_x10()
+    };
+    let steady = adc_to_temp_x10(2048);
+    let included = run(ClampedSamplePolicy::Include);
+    let excluded = run(ClampedSamplePolicy::Exclude);
+    if included != Some((4 * steady + 1250) / 5) || excluded != Some(steady) {
+        return fail(
+            "test_pipeline_clamped_policy",
+            format!("Include {included:?}, exclude {excluded:?}, steady {steady}"),
+        );
+    }
+
+    pass("test_pipeline_clamped_policy")
+}
+
+fn test_pipeline_trip_latency() -> TestResult {
+    let debounced = || Pipeline::new(1000, 950).with_state_machine(StateMachine::builder(1000, 950).debounce(3).build());
+    // 5-sample window: 4 samples to fill, then 3 for the debounce.
+    let bound = debounced().worst_case_trip_latency_samples();
+    if bound != 7 {
+        return fail("test_pipeline_trip_latency", format!("Latency {bound}"));
+    }
+
+    // A step from 90.0°C to exactly the trip point takes the full bound.
+    let mut p = debounced();
+    for _ in 0..6 {
+        let _ = p.step(temp_x10_to_adc(900));
+    }
+    let step = temp_x10_to_adc(1000);
+    let trip = (1..=20).find(|_| p.step(step) == Some(State::Unsafe));
+    if trip != Some(bound) {
+        return fail("test_pipeline_trip_latency", format!("Marginal step tripped after {trip:?}, bound {bound}"));
+    }
+
+    let mut unfiltered = Pipeline::new(1000, 950);
+    unfiltered.set_filtering(false);
+    if unfiltered.worst_case_trip_latency_samples() != 1 {
+        return fail("test_pipeline_trip_latency", "Unfiltered latency must be the debounce alone");
+    }
+    pass("test_pipeline_trip_latency")
+}
+
+fn test_state_machine_asymmetric_band() -> TestResult {
+    let mut sm = match StateMachine::from_setpoint_asymmetric(800, 20, 100) {
+        Ok(sm) if sm.high_x10() == 820 && sm.low_x10() == 700 => sm,
+        _ => return fail("test_state_machine_asymmetric_band", "Thresholds must be 82.0°C / 70.0°C"),
+    };
+
+    sm.evaluate(820);
+    // Below the setpoint but above the wider fall threshold: still Unsafe.
+    if sm.evaluate(750) != State::Unsafe || sm.evaluate(700) != State::Safe {
+        return fail("test_state_machine_asymmetric_band", "Recovery must happen at the fall threshold");
+    }
+
+    if StateMachine::from_setpoint_asymmetric(800, 20, 0).err() != Some(ConfigError::EmptyHysteresis)
+        || StateMachine::from_setpoint_asymmetric(TempX10::MAX, 1, 10).err() != Some(ConfigError::OutOfRange)
+    {
+        return fail("test_state_machine_asymmetric_band", "Invalid bands must be rejected");
+    }
+
+    pass("test_state_machine_asymmetric_band")
+}
+
+fn test_filter_impulse_response() -> TestResult {
+    if Filter::<5>::impulse_response_x1000() != [200; 5] {
+        return fail("test_filter_impulse_response", "5-tap boxcar must be five 200s");
+    }
+    let taps = Filter::<3>::impulse_response_x1000();
+    let sum: u32 = taps.iter().map(|&t| t as u32).sum();
+    if taps != [333; 3] || !(997..=1000).contains(&sum) {
+        return fail("test_filter_impulse_response", format!("3-tap boxcar {taps:?} sums to {sum}"));
+    }
+    pass("test_filter_impulse_response")
+}
+
+fn test_state_machine_reset_to_safe() -> TestResult {
+    let mut sm = StateMachine::builder(1000, 950).latch(true).build();
+    let config = sm.config();
+    sm.evaluate(1100);
+    sm.reset_to_safe();
+    if sm.state != State::Safe || sm.is_latched() || sm.config() != config {
+        return fail("test_state_machine_reset_to_safe", "Reset must clear state and latch but keep the config");
+    }
+    if sm.evaluate(1100) != State::Unsafe {
+        return fail("test_state_machine_reset_to_safe", "Over-threshold input must trip again after reset");
+    }
+
+    // The pipeline keeps its filter window: the next step already yields.
+    let mut p = Pipeline::new(1000, 950);
+    for _ in 0..6 {
+        let _ = p.step(3600);
+    }
+    p.reset_to_safe();
+    if p.state() != State::Safe || p.step(3600) != Some(State::Unsafe) {
+        return fail("test_state_machine_reset_to_safe", "Pipeline reset must keep the filter window");
+    }
+
+    pass("test_state_machine_reset_to_safe")
+}
+
+fn test_validate_trace() -> TestResult {
+    let limits = PlausibilityLimits { max_jump_x10: 50, rail_run: 3 };
+    let trace = [2000, 2010, 9000, 2020, 3000, 2030, 2040];
+    let issues = validate_trace(&trace, &limits);
+    let expected = [
+        TraceIssue { index: 2, kind: TraceIssueKind::OutOfRange(9000) },
+        TraceIssue {
+            index: 4,
+            kind: TraceIssueKind::Jump { from_x10: adc_to_temp_x10(2020), to_x10: adc_to_temp_x10(3000) },
+        },
+    ];
+    if issues != expected {
+        return fail("test_validate_trace", format!("Issues {issues:?}"));
+    }
+
+    let stuck = validate_trace(&[0, 0, 0, 0, 0], &PlausibilityLimits { max_jump_x10: 50, rail_run: 3 });
+    if stuck != [TraceIssue { index: 2, kind: TraceIssueKind::RailRun(SaturationState::StuckLow) }] {
+        return fail("test_validate_trace", format!("Rail run issues {stuck:?}"));
+    }
+
+    pass("test_validate_trace")
+}
+
+fn test_inverse_variance_filter() -> TestResult {
+    // Like `Filter`, the first output comes once the window is full and the
+    // oldest sample starts to be evicted.
+    let blend = |readings: &[(TempX10, u16)]| {
+        let mut f = InverseVarianceFilter::<5>::new();
+        readings.iter().map(|&(sample, variance)| f.update(sample, variance)).last().flatten()
+    };
+
+    let equal = [(0, 100), (490, 100), (495, 100), (500, 100), (505, 100), (510, 100)];
+    if blend(&equal[..5]).is_some() || blend(&equal) != Some(500) {
+        return fail("test_inverse_variance_filter", "Equal variances must give the plain mean");
+    }
+
+    // One confident reading among four noisy ones dominates.
+    let out = blend(&[(0, 1), (600, 400), (600, 400), (400, 1), (600, 400), (600, 400)]);
+    if !matches!(out, Some(t) if (400..=402).contains(&t)) {
+        return fail("test_inverse_variance_filter", format!("Confident sample must dominate, got {out:?}"));
+    }
+
+    // Zero variance is exact and overrides every other weight.
+    if blend(&[(0, 1), (700, 1), (300, 0), (700, 1), (700, 1), (700, 1)]) != Some(300) {
+        return fail("test_inverse_variance_filter", "Zero variance must take full weight");
+    }
+
+    pass("test_inverse_variance_filter")
+}
+
+fn test_calibration_validity_range() -> TestResult {
+    let Some(cal) = Calibration::two_point(205, 200, 812, 800) else {
+        return fail("test_calibration_validity_range", "Two-point calibration must build");
+    };
+    if !cal.is_within_calibrated_range(500) || cal.is_within_calibrated_range(1000) {
+        return fail("test_calibration_validity_range", "50°C is within 20-80°C, 100°C is not");
+    }
+    if !cal.is_within_calibrated_range(200) || !cal.is_within_calibrated_range(800) {
+        return fail("test_calibration_validity_range", "Reference points are within the range");
+    }
+    if Calibration::new(1, 1, 0).is_some_and(|c| c.is_within_calibrated_range(0)) {
+        return fail("test_calibration_validity_range", "Gain/offset calibration has no range");
+    }
+    pass("test_calibration_validity_range")
+}
+
+fn test_conversion_with_correction() -> TestResult {
+    let offset = |t: TempX10| t.saturating_add(50);
+    if adc_to_temp_x10_with(2048, offset) != adc_to_temp_x10(2048) + 50 {
+        return fail("test_conversion_with_correction", "Correction must apply to the conversion");
+    }
+    if adc_to_temp_x10_with(4095, offset) != TEMP_MAX_X10 || adc_to_temp_x10_with(0, |t| t - 50) != TEMP_MIN_X10 {
+        return fail("test_conversion_with_correction", "Corrected result must be clamped");
+    }
+    pass("test_conversion_with_correction")
+}
+
+fn test_severity_ordering() -> TestResult {
+    let ranked = [
+        State::Safe.severity(),
+        SaturationState::Transient.severity(),
+        State::Unsafe.severity(),
+        SaturationState::StuckHigh.severity(),
+    ];
+    if ranked != [severity::SAFE, severity::WARNING, severity::UNSAFE, severity::FAULT]
+        || !ranked.windows(2).all(|pair| pair[0] < pair[1])
+    {
+        return fail("test_severity_ordering", "Severity must rank Safe < Warning < Unsafe < Fault");
+    }
+    if SaturationState::Normal.severity() != State::Safe.severity()
+        || SaturationState::StuckLow.severity() != severity::FAULT
+    {
+        return fail("test_severity_ordering", "Equivalent conditions must share a severity");
+    }
+    pass("test_severity_ordering")
+}
+
+fn test_bus_packing() -> TestResult {
+    if pack_temp(-400) != [0x70, 0xFE] || pack_temp(1000) != [0xE8, 0x03] {
+        return fail("test_bus_packing", "Signed encoding must be little-endian two's complement");
+    }
+    for t in TempX10::MIN..=TempX10::MAX {
+        if unpack_temp(pack_temp(t)) != t {
+            return fail("test_bus_packing", format!("Signed round trip changed {t}"));
+        }
+    }
+
+    if pack_temp_offset(-400) != [0, 0] || pack_temp_offset(1250) != [0x72, 0x06] {
+        return fail("test_bus_packing", "Offset encoding must start at -40.0°C");
+    }
+    for t in TEMP_MIN_X10..=TEMP_MAX_X10 {
+        if unpack_temp_offset(pack_temp_offset(t)) != t {
+            return fail("test_bus_packing", format!("Offset round trip changed {t}"));
+        }
+    }
+    if pack_temp_offset(-1000) != [0, 0] || unpack_temp_offset([0xFF, 0xFF]) != TempX10::MAX {
+        return fail("test_bus_packing", "Offset encoding must clamp and saturate");
+    }
+
+    pass("test_bus_packing")
+}
+
+fn test_filter_has_settled() -> TestResult {
+    let mut quiet = Filter::<5>::new();
+    for sample in [500, 502, 499] {
+        let _ = quiet.update(sample);
+    }
+    if quiet.has_settled(5).is_some() {
+        return fail("test_filter_has_settled", "Partial window must not report");
+    }
+    for sample in [501, 503] {
+        let _ = quiet.update(sample);
+    }
+    if quiet.has_settled(5) != Some(true) || quiet.has_settled(3) != Some(false) {
+        return fail("test_filter_has_settled", "Quiet window spans 4 (0.4°C)");
+    }
+
+    let mut noisy = Filter::<5>::new();
+    for sample in [480, 520, 490, 515, 500] {
+        let _ = noisy.update(sample);
+    }
+    if noisy.has_settled(5) != Some(false) {
+        return fail("test_filter_has_settled", "Noisy window must not be settled");
+    }
+
+    pass("test_filter_has_settled")
+}
+
+fn test_x100_filter_and_state_machine() -> TestResult {
+    let Some(trip_code) = (0..=4095u16).find(|&adc| adc_to_temp_x100(adc) >= 10_000) else {
+        return fail("test_x100_filter_and_state_machine", "No code reaches 100.00C");
+    };
+    let below = adc_to_temp_x100(trip_code - 1);
+
+    let mut filter = Filter::<5, TempX100>::default();
+    let mut sm = StateMachine::<TempX100>::with_thresholds(10_000, 9_500);
+    for _ in 0..5 {
+        filter.update(below);
+    }
+    let filtered = filter.current();
+    if filtered != Some(below) || sm.evaluate(below) != State::Safe {
+        return fail(
+            "test_x100_filter_and_state_machine",
+            format!("{below} (0.01C) filtered to {filtered:?} or tripped"),
+        );
+    }
+
+    // Two codes apart average to a value no x10 sample can hold.
+    let mut filter = Filter::<2, TempX100>::default();
+    let (a, b) = (adc_to_temp_x100(trip_code), adc_to_temp_x100(trip_code + 1));
+    filter.update(a);
+    filter.update(b);
+    if filter.current() != Some((a + b) / 2) || (a + b) / 2 % 10 == 0 {
+        return fail(
+            "test_x100_filter_and_state_machine",
+            format!("{a} and {b} averaged to {:?}", filter.current()),
+        );
+    }
+
+    let mut sm_debounced = StateMachineBuilder::<TempX100>::new(10_000, 9_500).debounce(2).build();
+    let states = [sm.evaluate(a), sm_debounced.evaluate(a), sm_debounced.evaluate(a)];
+    if states != [State::Unsafe, State::Safe, State::Unsafe] {
+        return fail("test_x100_filter_and_state_machine", format!("Trip at {a} (0.01C) gave {states:?}"));
+    }
+    if sm.evaluate(9_501) != State::Unsafe || sm.evaluate(9_500) != State::Safe {
+        return fail("test_x100_filter_and_state_machine", "Recovery must happen at 95.00C, not 95.01C");
+    }
+
+    pass("test_x100_filter_and_state_machine")
+}
+
+/// Runs every TSIM test case in-process.
+pub fn run_suite() -> Vec<TestResult> {
+    run_suite_with(&SuiteConfig::default())
+}
+
+/// Runs every TSIM test case, checking the requirement tests against `config`.
+pub fn run_suite_with(config: &SuiteConfig) -> Vec<TestResult> {
+    #[cfg_attr(not(tsim_generated_lut), allow(unused_mut))]
+    let mut results = vec![
+        test_conversion_full_range(),
+        test_filter_noise_rejection(config),
+        test_threshold_and_hysteresis(config),
+        test_shared_magic_constant(),
+        test_estimate_lag_delayed_signal(),
+        test_filter_sum_saturates(),
+        test_filter_warmup_blend(),
+        test_transitions_ramp(),
+        test_pipeline_invalid_code(),
+        test_sensor_bank_aggregate(),
+        test_peak_hold(),
+        test_state_machine_record_round_trip(),
+        test_state_machine_record_rejects_garbage(),
+        test_hysteresis_pct(),
+        test_filter_update_tee(),
+        test_pipeline_rate_per_second(),
+        test_filter_chain_matches_manual(),
+        test_plausibility_gate_rejects_spike(),
+        test_conversion_rounding_modes(),
+        test_pipeline_step_fail_safe(),
+        test_clone_is_independent(),
+        test_filter_last_sample(),
+        test_microvolts_lm35(),
+        test_dyn_filter_matches_static(),
+        test_write_markdown(),
+        test_pipeline_filter_bypass(),
+        test_pipeline_effective_trip(),
+        test_try_evaluate_domain(),
+        test_read_variants(),
+        test_format_temp(),
+        test_filter_ac_rms(),
+        test_voting_truth_tables(),
+        test_calibration_large_gain(),
+        test_adc_stats(),
+        test_pipeline_snapshot_restore(),
+        test_thermocouple_cold_junction(),
+        test_oscillation_detector(),
+        test_linear_sensor_usable_range(),
+        test_sample_recorder_wraparound(),
+        test_threshold_presets(),
+        test_filter_settling_samples(),
+        test_suite_config_overrides(),
+        test_delta_log_round_trip(),
+        test_filter_noise_reduction(),
+        test_trip_edge_at_threshold(),
+        test_filter_trend_slope(),
+        test_adaptive_filter(),
+        test_req_map_unique(),
+        test_thermal_model_junction(),
+        test_transition_count(),
+        test_filter_update_missing(),
+        test_threshold_domain(),
+        test_filter_group_delay(),
+        test_parse_adc_stream(),
+        test_debounce_and_latch(),
+        test_filter_raw_sum(),
+        test_margin_to_trip(),
+        test_cached_converter(),
+        test_setpoint_error(),
+        test_filter_next_to_evict(),
+        test_pipeline_degraded_mode(),
+        test_from_setpoint_band(),
+        test_analyze_trace(),
+        test_recovery_confirmation(),
+        test_filter_sample_at(),
+        test_trimmed_mean_filter(),
+        test_is_chatter_safe(),
+        test_convert_exact(),
+        test_pipeline_warm_start(),
+        test_filter_seed_from(),
+        test_temp_to_adc_checked(),
+        test_conversion_x100_resolution(),
+        test_monotonic_trend(),
+        test_transition_table(),
+        test_raw_filter_before_conversion(),
+        test_state_machine_band(),
+        test_filter_update_fractional(),
+        test_sensor_bank_calibration(),
+        test_pipeline_just_primed(),
+        test_conversion_worst_case_error(),
+        test_conversion_vref_compensation(),
+        test_state_machine_within_sensor(),
+        test_state_machine_config(),
+        test_sliding_extrema_matches_brute_force(),
+        test_fahrenheit_conversion(),
+        test_state_machine_from_fahrenheit(),
+        test_state_machine_frozen(),
+        test_saturation_monitor(),
+        test_reading_from_u16(),
+        test_pipeline_clamped_policy(),
+        test_pipeline_trip_latency(),
+        test_state_machine_asymmetric_band(),
+        test_filter_impulse_response(),
+        test_state_machine_reset_to_safe(),
+        test_validate_trace(),
+        test_inverse_variance_filter(),
+        test_calibration_validity_range(),
+        test_conversion_with_correction(),
+        test_severity_ordering(),
+        test_bus_packing(),
+        test_filter_has_settled(),
+        test_x100_filter_and_state_machine(),
+    ];
+    #[cfg(tsim_generated_lut)]
+    results.push(test_generated_lut_matches_conversion());
+    results
+}
+
+/// Writes `results` as a JUnit XML `<testsuite>`.
+pub fn write_junit(
+    mut w: impl Write,
+    suite: &str,
+    config: &SuiteConfig,
+    results: &[TestResult],
+) -> io::Result<()> {
+    let failures = results.iter().filter(|r| !r.passed).count();
+
+    writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        w,
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"0\">",
+        suite,
+        results.len(),
+        failures
+    )?;
+    writeln!(w, "  <properties>")?;
+    for (name, value) in config_properties(config) {
+        writeln!(w, "    <property name=\"{name}\" value=\"{value}\"/>")?;
+    }
+    writeln!(w, "  </properties>")?;
+
+    for r in results {
+        writeln!(
+            w,
+            "  <testcase classname=\"{}\" name=\"{}\" time=\"0\">",
+            suite,
+            r.name
+        )?;
+        if !r.passed {
+            let msg = if r.message.is_empty() { "failed" } else { r.message.as_str() };
+            writeln!(w, "    <failure message=\"{}\"/>", xml_escape(msg))?;
+        }
+        writeln!(w, "  </testcase>")?;
+    }
+
+    writeln!(w, "</testsuite>")?;
+    Ok(())
+}
+
+fn config_properties(config: &SuiteConfig) -> [(&'static str, TempX10); 3] {
+    [
+        ("TSIM_HIGH_X10", config.high_x10),
+        ("TSIM_LOW_X10", config.low_x10),
+        ("TSIM_NOISE_BAND", config.noise_band),
+    ]
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Writes `results` as a GitHub-flavored Markdown table with a summary line.
+pub fn write_markdown(
+    mut w: impl Write,
+    suite: &str,
+    config: &SuiteConfig,
+    results: &[TestResult],
+) -> io::Result<()> {
+    let failures = results.iter().filter(|r| !r.passed).count();
+
+    writeln!(w, "## {suite}")?;
+    writeln!(w)?;
+    let properties: Vec<String> =
+        config_properties(config).iter().map(|(name, value)| format!("`{name}={value}`")).collect();
+    writeln!(w, "Configuration: {}", properties.join(", "))?;
+    writeln!(w)?;
+    writeln!(w, "| Name | Result | Message |")?;
+    writeln!(w, "|------|--------|---------|")?;
+    for r in results {
+        let result = if r.passed { "✅" } else { "❌" };
+        writeln!(w, "| {} | {} | {} |", r.name, result, markdown_escape(&r.message))?;
+    }
+    writeln!(w)?;
+    writeln!(w, "**{} passed, {} failed**", results.len() - failures, failures)?;
+    Ok(())
+}
+
+fn markdown_escape(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
@@ -1,7 +1,7 @@
 //! TSIM - Thermal Sensor Interface Module (Rust)
 //!
 //! OSQAR-CODE-TRACE (implementation tags)
-//! 
+//!
 //! REQ: REQ_SAFETY_001 REQ_SAFETY_002 REQ_SAFETY_003 REQ_FUNC_001 REQ_FUNC_002 REQ_FUNC_003 REQ_FUNC_004
 //! ARCH: ARCH_001 ARCH_DESIGN_001 ARCH_DESIGN_002 ARCH_DESIGN_003 ARCH_ERROR_001 ARCH_ERROR_002 ARCH_FUNC_001 ARCH_FUNC_002 ARCH_FUNC_003 ARCH_SEOOC_001 ARCH_SEOOC_002 ARCH_SIGNAL_001 ARCH_SIGNAL_002 ARCH_SIGNAL_003
 //!
@@ -11,34 +11,896 @@
 //!   - REQ_FUNC_003: Threshold detection (>=100°C)
 //!   - REQ_FUNC_004: Hysteresis recovery (<=95°C)
 
+use core::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// `log::debug!` when the `log` feature is enabled, nothing otherwise.
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+    };
+}
+
+/// `log::warn!` when the `log` feature is enabled, nothing otherwise.
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::warn!($($arg)*);
+    };
+}
+
+pub mod harness;
+
 /// Temperature in 0.1°C units (e.g., 100.0°C => 1000)
 pub type TempX10 = i16;
 
+/// Temperature in 0.01°C units (e.g., 100.00°C => 10000), for front-ends
+/// that resolve better than 0.1°C.
+///
+/// [`Filter`] and [`StateMachine`] accept it as their [`Temperature`]
+/// parameter, e.g. `Filter::<5, TempX100>`. [`Pipeline`] stays on
+/// [`TempX10`], the resolution REQ_FUNC_002..004 are specified in.
+pub type TempX100 = i32;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Integer temperature representation that [`Filter`] and
+/// [`StateMachine`] are generic over: [`TempX10`] (the default) or
+/// [`TempX100`]. The scale is the caller's; thresholds and samples must
+/// share it.
+pub trait Temperature:
+    Copy + Ord + Default + core::fmt::Debug + core::fmt::Display + Into<i64> + sealed::Sealed
+{
+    /// Accumulator for a window sum, twice as wide as the sample.
+    type Sum: Copy + Default + Into<i64> + core::ops::AddAssign + core::ops::SubAssign;
+
+    /// Converts to the accumulator type.
+    fn widen(self) -> Self::Sum;
+
+    /// Converts from `i64`, saturating at the type's limits.
+    fn saturating_from(value: i64) -> Self;
+}
+
+macro_rules! impl_temperature {
+    ($($t:ty => $sum:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl Temperature for $t {
+            type Sum = $sum;
+
+            fn widen(self) -> $sum {
+                self as $sum
+            }
+
+            fn saturating_from(value: i64) -> Self {
+                value.clamp(<$t>::MIN as i64, <$t>::MAX as i64) as $t
+            }
+        }
+    )*};
+}
+
+impl_temperature!(TempX10 => i32, TempX100 => i64);
+
+/// Lowest temperature the TSIM sensor can report (-40.0°C).
+pub const TEMP_MIN_X10: TempX10 = -400;
+
+/// Highest temperature the TSIM sensor can report (+125.0°C).
+pub const TEMP_MAX_X10: TempX10 = 1250;
+
+/// Declares [`REQ_MAP`] and checks at compile time that every listed item
+/// exists, so the map cannot drift from the code.
+macro_rules! req_map {
+    ($($id:literal => $kind:ident $($item:ident)::+,)*) => {
+        /// Requirement ID -> implementing item, for external traceability
+        /// tooling. One entry per requirement.
+        pub const REQ_MAP: &[(&str, &str)] = &[$(($id, stringify!($($item)::+))),*];
+
+        const _: () = {
+            #[allow(dead_code)]
+            fn items_exist() {
+                $(req_map!(@check $kind $($item)::+);)*
+            }
+        };
+    };
+    (@check type $ty:path) => {
+        let _ = core::mem::size_of::<$ty>();
+    };
+    // `<Type>::method` rather than `Type::method`, so that default type
+    // parameters apply, as they do for `type` entries.
+    (@check fn $ty:ident :: $method:ident) => {
+        let _ = <$ty>::$method;
+    };
+    (@check fn $f:path) => {
+        let _ = $f;
+    };
+}
+
+req_map! {
+    "REQ_SAFETY_001" => type Pipeline,
+    "REQ_SAFETY_002" => fn Pipeline::step,
+    "REQ_SAFETY_003" => fn Pipeline::state,
+    "REQ_FUNC_001" => fn adc_to_temp_x10,
+    "REQ_FUNC_002" => type Filter,
+    "REQ_FUNC_003" => fn StateMachine::evaluate,
+    "REQ_FUNC_004" => fn StateMachine::evaluate,
+}
+
+/// A measured temperature, kept apart from [`Setpoint`] by the type system.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Measured(pub TempX10);
+
+/// A control setpoint, kept apart from [`Measured`] by the type system.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Setpoint(pub TempX10);
+
+/// Control error `setpoint - measured` in 0.1°C, saturated to `i16`;
+/// positive when the measurement is below the setpoint.
+///
+/// The arguments cannot be swapped or passed bare:
+///
+/// ```compile_fail
+/// use tsim::{error_x10, Measured, Setpoint};
+/// error_x10(Measured(600), Setpoint(550));
+/// ```
+///
+/// ```compile_fail
+/// use tsim::{error_x10, Measured};
+/// error_x10(600, Measured(550));
+/// ```
+pub fn error_x10(setpoint: Setpoint, measured: Measured) -> i16 {
+    (setpoint.0 as i32 - measured.0 as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum State {
     Safe,
     Unsafe,
 }
 
+/// Alarm severity scale shared by [`State::severity`] and
+/// [`SaturationState::severity`]; aggregate by taking the maximum.
+pub mod severity {
+    /// Nothing to report.
+    pub const SAFE: u8 = 0;
+    /// Suspicious but not yet confirmed, e.g. a rail run still shorter than
+    /// the fault threshold.
+    pub const WARNING: u8 = 1;
+    /// Over-temperature declared.
+    pub const UNSAFE: u8 = 2;
+    /// Sensor fault: the temperature itself is unknown.
+    pub const FAULT: u8 = 3;
+}
+
+impl State {
+    /// [`severity::SAFE`] or [`severity::UNSAFE`].
+    pub fn severity(&self) -> u8 {
+        match self {
+            State::Safe => severity::SAFE,
+            State::Unsafe => severity::UNSAFE,
+        }
+    }
+
+    /// 2oo2 voting: `Unsafe` only if both inputs are `Unsafe`.
+    pub fn and(a: State, b: State) -> State {
+        if a == State::Unsafe && b == State::Unsafe {
+            State::Unsafe
+        } else {
+            State::Safe
+        }
+    }
+
+    /// 1oo2 voting: `Unsafe` if either input is `Unsafe`.
+    pub fn or(a: State, b: State) -> State {
+        if a == State::Unsafe || b == State::Unsafe {
+            State::Unsafe
+        } else {
+            State::Safe
+        }
+    }
+}
+
 /// REQ_FUNC_001: ADC (12-bit) to temperature conversion.
 /// Output range: -40.0°C..+125.0°C => -400..1250 (0.1°C)
 pub fn adc_to_temp_x10(adc_counts: u16) -> TempX10 {
+    adc_formula_x10(adc_counts)
+}
+
+include!("adc_formula.rs");
+
+/// [`adc_to_temp_x10`] for every 12-bit code, generated by `build.rs` from
+/// the same arithmetic. Only built by Cargo, which runs the build script.
+#[cfg(tsim_generated_lut)]
+pub static ADC_TO_TEMP_X10_LUT: [TempX10; 4096] =
+    include!(concat!(env!("OUT_DIR"), "/adc_to_temp_x10_lut.rs"));
+
+/// [`adc_to_temp_x10`] followed by an ad-hoc `correct`ion, for experiments
+/// before committing to a [`Calibration`]. The result is clamped back to
+/// `TEMP_MIN_X10..=TEMP_MAX_X10`.
+///
+/// `correct` must be pure: it may be called any number of times, and the
+/// same input must always give the same output.
+pub fn adc_to_temp_x10_with<F: Fn(TempX10) -> TempX10>(adc: u16, correct: F) -> TempX10 {
+    correct(adc_to_temp_x10(adc)).clamp(TEMP_MIN_X10, TEMP_MAX_X10)
+}
+
+/// [`adc_to_temp_x10`], also reporting whether the code was above 4095 and
+/// clamped to the +125.0°C rail. Codes 0 and 4095 are real conversions and
+/// report `false`; see [`SaturationMonitor`] for those.
+pub fn adc_to_temp_x10_clamped(adc_counts: u16) -> (TempX10, bool) {
+    (adc_to_temp_x10(adc_counts), adc_counts > 4095)
+}
+
+/// [`adc_to_temp_x10`] at 0.01°C resolution, rounded to nearest.
+///
+/// One ADC code is about 0.04°C, so this keeps detail the x10 conversion
+/// rounds away. Feed it to a `Filter::<N, TempX100>` and a
+/// `StateMachine::<TempX100>` to keep that detail through the chain;
+/// narrowing with [`temp_x100_to_x10`] instead gives no extra precision
+/// (and may differ by one step, see there).
+pub fn adc_to_temp_x100(adc_counts: u16) -> TempX100 {
+    let adc = adc_counts.min(4095) as i64;
+    (TEMP_MIN_X10 as i64 * 10 + div_round(adc * 16_500, 4095)) as TempX100
+}
+
+/// Rounds a [`TempX100`] to the nearest [`TempX10`] (ties away from
+/// zero), saturating. Rounding twice, `temp_x100_to_x10(adc_to_temp_x100(c))`
+/// can be one step off `adc_to_temp_x10(c)`.
+pub fn temp_x100_to_x10(temp_x100: TempX100) -> TempX10 {
+    div_round(temp_x100 as i64, 10).clamp(TempX10::MIN as i64, TempX10::MAX as i64) as TempX10
+}
+
+/// Fahrenheit (0.1°F) to Celsius (0.1°C), rounded to nearest with ties away
+/// from zero.
+pub fn fahrenheit_x10_to_celsius_x10(f_x10: i16) -> TempX10 {
+    div_round((f_x10 as i64 - 320) * 5, 9) as TempX10
+}
+
+/// Celsius (0.1°C) to Fahrenheit (0.1°F), rounded like
+/// [`fahrenheit_x10_to_celsius_x10`] and saturating.
+pub fn celsius_x10_to_fahrenheit_x10(temp_x10: TempX10) -> i16 {
+    (div_round(temp_x10 as i64 * 9, 5) + 320).clamp(i16::MIN as i64, i16::MAX as i64) as i16
+}
+
+/// Bus encoding of a temperature: 0.1°C per bit, two's complement,
+/// little-endian (`[low byte, high byte]`). Covers the whole `TempX10`
+/// range.
+pub fn pack_temp(temp_x10: TempX10) -> [u8; 2] {
+    temp_x10.to_le_bytes()
+}
+
+/// Decodes [`pack_temp`].
+pub fn unpack_temp(bytes: [u8; 2]) -> TempX10 {
+    TempX10::from_le_bytes(bytes)
+}
+
+/// Unsigned bus encoding: `temp_x10 - TEMP_MIN_X10` (so -40.0°C is 0 and
+/// +125.0°C is 1650), 0.1°C per bit, little-endian. Temperatures outside
+/// `TEMP_MIN_X10..=TEMP_MAX_X10` are clamped first.
+pub fn pack_temp_offset(temp_x10: TempX10) -> [u8; 2] {
+    let clamped = temp_x10.clamp(TEMP_MIN_X10, TEMP_MAX_X10);
+    ((clamped - TEMP_MIN_X10) as u16).to_le_bytes()
+}
+
+/// Decodes [`pack_temp_offset`], saturating for raw values beyond
+/// `TempX10`.
+pub fn unpack_temp_offset(bytes: [u8; 2]) -> TempX10 {
+    let raw = u16::from_le_bytes(bytes) as i32 + TEMP_MIN_X10 as i32;
+    raw.min(TempX10::MAX as i32) as TempX10
+}
+
+/// Inverse of [`adc_to_temp_x10`]: nearest ADC code for `temp_x10`,
+/// clamped to `0..=4095`. `adc_to_temp_x10(temp_x10_to_adc(t)) == t` for
+/// every `t` in the sensor range.
+pub fn temp_x10_to_adc(temp_x10: TempX10) -> u16 {
+    temp_x10_to_adc_checked(temp_x10).0
+}
+
+/// Like [`temp_x10_to_adc`], also reporting whether `temp_x10` was outside
+/// `TEMP_MIN_X10..=TEMP_MAX_X10` and had to be clamped.
+pub fn temp_x10_to_adc_checked(temp_x10: TempX10) -> (u16, bool) {
+    let clamped = temp_x10.clamp(TEMP_MIN_X10, TEMP_MAX_X10);
+    let adc = div_round((clamped as i64 - TEMP_MIN_X10 as i64) * 4095, 1650);
+    (adc as u16, clamped != temp_x10)
+}
+
+/// [`adc_to_temp_x10`] corrected for ADC reference drift by scaling the
+/// code by `nominal_vref_mv / measured_vref_mv` before conversion.
+///
+/// `measured_vref_mv` is the bandgap channel read this cycle, expressed as
+/// if the reference were nominal; `nominal_vref_mv` is what that channel
+/// reads at the nominal reference. A low bandgap reading means the real
+/// reference is high, so raw codes understate the input and get scaled up.
+/// The corrected code saturates at 4095; a zero `measured_vref_mv` (no
+/// usable bandgap read) leaves the code uncorrected.
+pub fn adc_to_temp_x10_vref(adc: u16, measured_vref_mv: u16, nominal_vref_mv: u16) -> TempX10 {
+    let adc = adc.min(4095);
+    if measured_vref_mv == 0 {
+        return adc_to_temp_x10(adc);
+    }
+    let corrected = div_round(adc as i64 * nominal_vref_mv as i64, measured_vref_mv as i64);
+    adc_to_temp_x10(corrected.min(4095) as u16)
+}
+
+/// Largest difference, in 0.1°C, between [`adc_to_temp_x10`] and the exact
+/// `f64` conversion rounded to nearest, over all 4096 codes.
+///
+/// Computed rather than asserted so the qualification report can cite it
+/// and CI rechecks it; the documented bound is 1 (0.1°C). The divisor is
+/// odd, so the integer rounding never meets a tie and the result is 0.
+/// This is the fixed-point error only, not the sensor's quantization step.
+pub fn max_conversion_error_x10() -> u16 {
+    (0..=4095u16)
+        .map(|adc| {
+            let exact = (-400.0 + adc as f64 * 1650.0 / 4095.0).round();
+            (adc_to_temp_x10(adc) as f64 - exact).abs() as u16
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Temperature and fault status of one raw ADC read.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reading {
+    /// Plausible conversion.
+    Ok(TempX10),
+    /// Code 0: input shorted to ground or open with pull-down.
+    StuckLow,
+    /// Code 4095: input shorted to the reference or open with pull-up.
+    StuckHigh,
+    /// Code above 4095: not a valid 12-bit conversion.
+    OutOfRange,
+}
+
+/// Recommended entry point for raw reads: classifies `adc_counts` and only
+/// yields a temperature when the code is not a sensor fault.
+///
+/// The rails are treated as faults because a healthy sensor in its rated
+/// range never drives the ADC fully to either end.
+pub fn read(adc_counts: u16) -> Reading {
+    match adc_counts {
+        0 => Reading::StuckLow,
+        4095 => Reading::StuckHigh,
+        4096.. => Reading::OutOfRange,
+        _ => Reading::Ok(adc_to_temp_x10(adc_counts)),
+    }
+}
+
+impl From<u16> for Reading {
+    /// Same as [`read`].
+    fn from(adc_counts: u16) -> Self {
+        read(adc_counts)
+    }
+}
+
+/// Verdict of a [`SaturationMonitor`] update.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SaturationState {
+    /// Not on a rail.
+    Normal,
+    /// On a rail, but not yet for the configured run length.
+    Transient,
+    /// Sustained code 0: input shorted to ground or open with pull-down.
+    StuckLow,
+    /// Sustained code 4095: input shorted to the reference or open with
+    /// pull-up.
+    StuckHigh,
+}
+
+/// Flags a rail fault only after `run_length` consecutive readings on the
+/// same rail, unlike [`read`], which reports every single rail code.
+#[derive(Clone, Debug)]
+pub struct SaturationMonitor {
+    run_length: u32,
+    rail: Option<u16>,
+    run: u32,
+}
+
+impl SaturationState {
+    /// [`severity::SAFE`] when normal, [`severity::WARNING`] while a rail
+    /// run is transient and [`severity::FAULT`] once it is sustained.
+    pub fn severity(&self) -> u8 {
+        match self {
+            SaturationState::Normal => severity::SAFE,
+            SaturationState::Transient => severity::WARNING,
+            SaturationState::StuckLow | SaturationState::StuckHigh => severity::FAULT,
+        }
+    }
+}
+
+impl SaturationMonitor {
+    /// `run_length` 0 and 1 both fault on the first rail reading.
+    pub fn new(run_length: u32) -> Self {
+        Self {
+            run_length: run_length.max(1),
+            rail: None,
+            run: 0,
+        }
+    }
+
+    /// Any off-rail code, including an out-of-range one, ends the run; so
+    /// does a jump to the opposite rail, which starts a new one.
+    pub fn update(&mut self, adc: u16) -> SaturationState {
+        if adc != 0 && adc != 4095 {
+            self.rail = None;
+            self.run = 0;
+            return SaturationState::Normal;
+        }
+        if self.rail != Some(adc) {
+            self.rail = Some(adc);
+            self.run = 0;
+        }
+        self.run = self.run.saturating_add(1);
+        match adc {
+            _ if self.run < self.run_length => SaturationState::Transient,
+            0 => SaturationState::StuckLow,
+            _ => SaturationState::StuckHigh,
+        }
+    }
+}
+
+/// Error parsing a binary ADC capture, see [`parse_adc_stream`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Input length in bytes; a capture is a whole number of `u16` samples.
+    OddLength(usize),
+}
+
+/// Lazily converts a capture of little-endian `u16` ADC codes with
+/// [`adc_to_temp_x10`].
+pub fn parse_adc_stream(bytes: &[u8]) -> Result<impl Iterator<Item = TempX10> + '_, ParseError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(ParseError::OddLength(bytes.len()));
+    }
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pair| adc_to_temp_x10(u16::from_le_bytes([pair[0], pair[1]]))))
+}
+
+/// Formats a temperature as degrees Celsius with 0 or 1 decimals.
+///
+/// `decimals` above 1 are treated as 1 (the native 0.1°C resolution). With 0
+/// decimals the value is rounded half away from zero, and a result of zero
+/// is never printed as `-0`. No unit suffix is written.
+pub fn format_temp(temp_x10: TempX10, decimals: u8) -> TempDisplay {
+    TempDisplay {
+        temp_x10,
+        decimals: decimals.min(1),
+    }
+}
+
+/// [`core::fmt::Display`] adapter returned by [`format_temp`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TempDisplay {
+    temp_x10: TempX10,
+    decimals: u8,
+}
+
+impl core::fmt::Display for TempDisplay {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value = self.temp_x10 as i32;
+        let magnitude = value.unsigned_abs();
+        if self.decimals == 0 {
+            let whole = (magnitude + 5) / 10;
+            let sign = if value < 0 && whole != 0 { "-" } else { "" };
+            write!(f, "{sign}{whole}")
+        } else {
+            let sign = if value < 0 { "-" } else { "" };
+            write!(f, "{sign}{}.{}", magnitude / 10, magnitude % 10)
+        }
+    }
+}
+
+/// Rounding applied to the `adc * 1650 / 4095` quotient.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round to nearest, ties up (what [`adc_to_temp_x10`] uses).
+    HalfUp,
+    /// Round to nearest, ties to even.
+    HalfEven,
+    /// Truncate (the quotient is never negative, so this is also floor).
+    TowardZero,
+}
+
+/// [`adc_to_temp_x10`] with an explicit rounding mode.
+///
+/// Because 4095 is odd, `adc * 1650 / 4095` never lands exactly on a half,
+/// so `HalfUp` and `HalfEven` agree for every 12-bit code; both are kept so
+/// a reference model's declared mode can be named verbatim.
+pub fn adc_to_temp_x10_with_rounding(adc_counts: u16, mode: RoundingMode) -> TempX10 {
     let adc = adc_counts.min(4095);
 
-    // celsius = -40 + adc * (165 / 4095)
-    // x10: temp_x10 = -400 + adc * (1650 / 4095)
     let numerator: i32 = (adc as i32) * 1650;
-    let scaled: i32 = (numerator + 2047) / 4095; // round
-    let mut temp_x10: i32 = -400 + scaled;
+    let quotient = numerator / 4095;
+    let twice_remainder = 2 * (numerator % 4095);
+    let round_up = match mode {
+        RoundingMode::HalfUp => twice_remainder >= 4095,
+        RoundingMode::HalfEven => {
+            twice_remainder > 4095 || (twice_remainder == 4095 && quotient % 2 == 1)
+        }
+        RoundingMode::TowardZero => false,
+    };
+    let temp_x10: i32 = -400 + quotient + round_up as i32;
 
-    if temp_x10 < -400 {
-        temp_x10 = -400;
+    temp_x10.clamp(-400, 1250) as TempX10
+}
+
+/// Converts a fixed-size burst with [`adc_to_temp_x10`] into a stack array.
+///
+/// No allocation: the result has the input's length, fixed at compile time.
+///
+/// ```
+/// fn burst(adc: &[u16; 3]) -> [tsim::TempX10; 3] {
+///     tsim::convert_exact(adc)
+/// }
+///
+/// assert_eq!(burst(&[0, 2048, 4095]), [-400, 425, 1250]);
+/// ```
+pub fn convert_exact<const M: usize>(adc: &[u16; M]) -> [TempX10; M] {
+    adc.map(adc_to_temp_x10)
+}
+
+/// Sensor with a linear voltage transfer characteristic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LinearSensor {
+    uv_at_0c: i32,
+    uv_per_c: i32,
+    min_x10: TempX10,
+    max_x10: TempX10,
+    usable_range: Option<(TempX10, TempX10)>,
+}
+
+impl LinearSensor {
+    /// LM35-class sensor: 10 mV/°C, 0 V at 0°C, rated -55°C..+150°C.
+    pub const LM35: LinearSensor = LinearSensor {
+        uv_at_0c: 0,
+        uv_per_c: 10_000,
+        min_x10: -550,
+        max_x10: 1500,
+        usable_range: None,
+    };
+
+    /// Output is `uv_at_0c + uv_per_c * celsius`; results are clamped to
+    /// `min_x10..=max_x10`. Returns `None` for a zero slope or an empty range.
+    pub const fn new(
+        uv_at_0c: i32,
+        uv_per_c: i32,
+        min_x10: TempX10,
+        max_x10: TempX10,
+    ) -> Option<Self> {
+        if uv_per_c == 0 || min_x10 > max_x10 {
+            return None;
+        }
+        Some(Self {
+            uv_at_0c,
+            uv_per_c,
+            min_x10,
+            max_x10,
+            usable_range: None,
+        })
+    }
+
+    /// Restricts [`LinearSensor::convert_checked`] to the specified band,
+    /// which may be narrower than the electrical range. Returns `None` if
+    /// `low_x10 > high_x10`.
+    pub const fn with_usable_range(mut self, low_x10: TempX10, high_x10: TempX10) -> Option<Self> {
+        if low_x10 > high_x10 {
+            return None;
+        }
+        self.usable_range = Some((low_x10, high_x10));
+        Some(self)
+    }
+
+    /// Converts like [`microvolts_to_temp_x10`], but fails when the result
+    /// lies outside the usable range (if one is configured).
+    pub fn convert_checked(&self, uv: i32) -> Result<TempX10, OutOfSpec> {
+        let temp_x10 = microvolts_to_temp_x10(uv, self);
+        match self.usable_range {
+            Some((low, high)) if !(low..=high).contains(&temp_x10) => Err(OutOfSpec(temp_x10)),
+            _ => Ok(temp_x10),
+        }
+    }
+
+    /// Lowest temperature the sensor can report.
+    pub fn min_x10(&self) -> TempX10 {
+        self.min_x10
+    }
+
+    /// Highest temperature the sensor can report.
+    pub fn max_x10(&self) -> TempX10 {
+        self.max_x10
+    }
+}
+
+/// Reading outside a sensor's usable range; holds the converted value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OutOfSpec(pub TempX10);
+
+/// Converts a calibrated sensor voltage (µV) to temperature.
+///
+/// Rounds to the nearest 0.1°C with ties away from zero, then clamps to the
+/// sensor's rated range.
+pub fn microvolts_to_temp_x10(uv: i32, sensor: &LinearSensor) -> TempX10 {
+    let mut numerator = (uv as i64 - sensor.uv_at_0c as i64) * 10;
+    let mut denominator = sensor.uv_per_c as i64;
+    if denominator < 0 {
+        numerator = -numerator;
+        denominator = -denominator;
+    }
+
+    let rounded = div_round(numerator, denominator);
+    rounded.clamp(sensor.min_x10 as i64, sensor.max_x10 as i64) as TempX10
+}
+
+/// Raw reading to temperature conversion, e.g. [`LinearSensor`] from µV.
+pub trait Converter {
+    fn convert(&self, raw: i32) -> TempX10;
+}
+
+impl Converter for LinearSensor {
+    fn convert(&self, uv: i32) -> TempX10 {
+        microvolts_to_temp_x10(uv, self)
+    }
+}
+
+/// One-entry memo in front of a [`Converter`]: a reading equal to the
+/// previous one returns the previous temperature without reconverting.
+#[derive(Clone, Debug)]
+pub struct CachedConverter<C = LinearSensor> {
+    inner: C,
+    last: Option<(i32, TempX10)>,
+}
+
+impl<C: Converter> CachedConverter<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner, last: None }
+    }
+
+    pub fn convert(&mut self, raw: i32) -> TempX10 {
+        match self.last {
+            Some((cached_raw, temp_x10)) if cached_raw == raw => temp_x10,
+            _ => {
+                let temp_x10 = self.inner.convert(raw);
+                self.last = Some((raw, temp_x10));
+                temp_x10
+            }
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+/// `numerator / denominator` rounded to nearest, ties away from zero.
+/// `denominator` must be positive.
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    let half = denominator / 2;
+    if numerator >= 0 {
+        (numerator + half) / denominator
+    } else {
+        (numerator - half) / denominator
+    }
+}
+
+/// Linear calibration `(t - origin) * gain_num / gain_den + offset`.
+///
+/// Intermediates are `i64`: with an `i16` temperature and `i32` gain terms
+/// the product stays below 2^47, so every representable gain ratio is
+/// supported without overflow. Results are rounded to nearest (ties away
+/// from zero) and clamped to `TEMP_MIN_X10..=TEMP_MAX_X10`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    origin_x10: TempX10,
+    gain_num: i32,
+    gain_den: i32,
+    offset_x10: i32,
+    reference_span_x10: Option<(TempX10, TempX10)>,
+}
+
+impl Calibration {
+    /// Gain/offset calibration around 0°C. Returns `None` if `gain_den == 0`.
+    pub fn new(gain_num: i32, gain_den: i32, offset_x10: i32) -> Option<Self> {
+        Self::normalized(0, gain_num, gain_den, offset_x10)
+    }
+
+    /// Two-point calibration mapping measured `raw_*` temperatures onto the
+    /// reference temperatures `ref_*`. Returns `None` if the raw points
+    /// coincide.
+    pub fn two_point(
+        raw_lo: TempX10,
+        ref_lo: TempX10,
+        raw_hi: TempX10,
+        ref_hi: TempX10,
+    ) -> Option<Self> {
+        let calibration = Self::normalized(
+            raw_lo,
+            ref_hi as i32 - ref_lo as i32,
+            raw_hi as i32 - raw_lo as i32,
+            ref_lo as i32,
+        )?;
+        Some(Self {
+            reference_span_x10: Some((ref_lo.min(ref_hi), ref_lo.max(ref_hi))),
+            ..calibration
+        })
+    }
+
+    /// Whether a calibrated temperature lies between the two reference
+    /// points (inclusive); outside them [`Calibration::apply`] extrapolates.
+    /// Always `false` for [`Calibration::new`], which has no reference
+    /// points.
+    pub fn is_within_calibrated_range(&self, temp_x10: TempX10) -> bool {
+        self.reference_span_x10
+            .is_some_and(|(lo, hi)| (lo..=hi).contains(&temp_x10))
+    }
+
+    fn normalized(
+        origin_x10: TempX10,
+        gain_num: i32,
+        gain_den: i32,
+        offset_x10: i32,
+    ) -> Option<Self> {
+        if gain_den == 0 {
+            return None;
+        }
+        Some(Self {
+            origin_x10,
+            gain_num,
+            gain_den,
+            offset_x10,
+            reference_span_x10: None,
+        })
+    }
+
+    pub fn apply(&self, temp_x10: TempX10) -> TempX10 {
+        let mut numerator = (temp_x10 as i64 - self.origin_x10 as i64) * self.gain_num as i64;
+        let mut denominator = self.gain_den as i64;
+        if denominator < 0 {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+        let calibrated = div_round(numerator, denominator) + self.offset_x10 as i64;
+        calibrated.clamp(TEMP_MIN_X10 as i64, TEMP_MAX_X10 as i64) as TempX10
+    }
+}
+
+/// Type K reference table (NIST ITS-90, reference junction at 0°C):
+/// `(temperature x10, EMF in µV)`, strictly increasing in both columns.
+const TYPE_K_TABLE: [(i32, i32); 22] = [
+    (-2000, -5891),
+    (-1000, -3554),
+    (-500, -1889),
+    (0, 0),
+    (250, 1000),
+    (500, 2023),
+    (1000, 4096),
+    (1500, 6138),
+    (2000, 8138),
+    (2500, 10153),
+    (3000, 12209),
+    (3500, 14293),
+    (4000, 16397),
+    (4500, 18516),
+    (5000, 20644),
+    (6000, 24905),
+    (7000, 29129),
+    (8000, 33275),
+    (9000, 37326),
+    (10000, 41276),
+    (11000, 45119),
+    (12000, 48838),
+];
+
+/// Piecewise-linear lookup in `table` from column `from` to the other one,
+/// clamped to the table ends.
+fn interpolate(table: &[(i32, i32)], value: i32, from_emf: bool) -> i32 {
+    let key = |p: &(i32, i32)| if from_emf { (p.1, p.0) } else { *p };
+    let (first, last) = (key(&table[0]), key(&table[table.len() - 1]));
+    if value <= first.0 {
+        return first.1;
+    }
+    if value >= last.0 {
+        return last.1;
+    }
+    let upper = table
+        .iter()
+        .position(|p| key(p).0 >= value)
+        .unwrap_or(table.len() - 1);
+    let (x0, y0) = key(&table[upper - 1]);
+    let (x1, y1) = key(&table[upper]);
+    y0 + div_round((value - x0) as i64 * (y1 - y0) as i64, (x1 - x0) as i64) as i32
+}
+
+/// Nonlinear sensor characteristic (e.g. an NTC divider) as a
+/// piecewise-linear `(adc_counts, temp_x10)` table, ascending in ADC code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ThermistorTable {
+    points: &'static [(i32, i32)],
+}
+
+impl ThermistorTable {
+    /// Returns `None` unless there are at least two points with strictly
+    /// ascending ADC codes.
+    pub const fn new(points: &'static [(i32, i32)]) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+        let mut i = 1;
+        while i < points.len() {
+            if points[i].0 <= points[i - 1].0 {
+                return None;
+            }
+            i += 1;
+        }
+        Some(Self { points })
+    }
+
+    /// Interpolated temperature, clamped to the table ends.
+    pub fn convert(&self, adc_counts: u16) -> TempX10 {
+        Converter::convert(self, adc_counts as i32)
     }
-    if temp_x10 > 1250 {
-        temp_x10 = 1250;
+}
+
+/// Raw reading is the ADC code, e.g. for [`Pipeline::with_converter`].
+impl Converter for ThermistorTable {
+    fn convert(&self, adc_counts: i32) -> TempX10 {
+        let temp_x10 = interpolate(self.points, adc_counts, false);
+        temp_x10.clamp(TempX10::MIN as i32, TempX10::MAX as i32) as TempX10
+    }
+}
+
+/// Type K thermocouple with cold-junction compensation.
+///
+/// The ADC front-end is linear: `emf_uv = offset_uv + adc * nv_per_count / 1000`.
+/// The cold-junction temperature is converted to its equivalent EMF, added
+/// to the measured EMF, and the sum mapped back to the hot-junction
+/// temperature. Both directions interpolate linearly in the NIST table
+/// (-200°C..+1200°C), which keeps the error below about 1°C.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ThermocoupleSensor {
+    offset_uv: i32,
+    nv_per_count: i32,
+}
+
+impl ThermocoupleSensor {
+    pub fn type_k(offset_uv: i32, nv_per_count: i32) -> Self {
+        Self {
+            offset_uv,
+            nv_per_count,
+        }
+    }
+
+    /// Compensated hot-junction temperature, clamped to the table range.
+    pub fn hot_junction_x10(&self, adc_counts: u16, cold_junction_x10: TempX10) -> TempX10 {
+        let measured_uv =
+            self.offset_uv as i64 + adc_counts as i64 * self.nv_per_count as i64 / 1000;
+        let cold_uv = interpolate(&TYPE_K_TABLE, cold_junction_x10 as i32, false) as i64;
+        let total_uv = (measured_uv + cold_uv).clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        interpolate(&TYPE_K_TABLE, total_uv, true) as TempX10
     }
+}
+
+/// Single thermal-resistance model `T_j = T_ambient + P * theta_ja`.
+///
+/// Assumes steady state and a linear, temperature-independent junction-to-
+/// ambient resistance; thermal capacitance and other heat paths are
+/// ignored, so fast power steps are overestimated until the package heats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ThermalModel {
+    /// Junction-to-ambient resistance in 0.001 °C/W.
+    pub theta_ja_x1000: u32,
+}
 
-    temp_x10 as TempX10
+impl ThermalModel {
+    /// Estimated junction temperature for `power_mw` dissipated at
+    /// `ambient_x10`, rounded and saturated to `TempX10`.
+    pub fn junction_temp_x10(&self, ambient_x10: TempX10, power_mw: u32) -> TempX10 {
+        // mW * (0.001 °C/W) = 1e-6 °C, i.e. 1e-5 in 0.1 °C units.
+        let rise_x10 = div_round(power_mw as i64 * self.theta_ja_x1000 as i64, 100_000);
+        (ambient_x10 as i64 + rise_x10).clamp(TempX10::MIN as i64, TempX10::MAX as i64) as TempX10
+    }
 }
 
 extern "C" {
@@ -51,72 +913,2312 @@ pub fn shared_magic() -> i32 {
 }
 
 /// REQ_FUNC_002: 5-sample moving average filter.
-pub struct Filter {
-    window: [TempX10; 5],
+///
+/// The window length `N` defaults to the required 5 samples; other lengths
+/// are available for analysis via `Filter::<N>::default()`. The sample type
+/// `T` defaults to [`TempX10`]; see [`Temperature`].
+#[derive(Clone)]
+pub struct Filter<const N: usize = 5, T: Temperature = TempX10> {
+    window: [T; N],
     count: usize,
     index: usize,
-    sum: i32,
+    sum: T::Sum,
+    warmup_fallback: Option<T>,
 }
 
 impl Filter {
     pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize, T: Temperature> Filter<N, T> {
+    /// Filter that produces output during warm-up instead of `None`.
+    ///
+    /// While the window fills, the output blends `fallback` with the partial
+    /// average of the `count` samples seen so far, weighting the partial
+    /// average by `count / N` and `fallback` by the rest: the first output
+    /// leans `(N - 1) / N` on `fallback` and the `N`th is the full average.
+    /// This gives a bumpless transfer after [`Filter::reset`], e.g. on a
+    /// sensor range switch.
+    pub fn with_warmup(fallback: T) -> Self {
         Self {
-            window: [0; 5],
-            count: 0,
-            index: 0,
-            sum: 0,
+            warmup_fallback: Some(fallback),
+            ..Self::default()
         }
     }
 
-    /// Returns `Some(filtered)` only once the window is full.
-    pub fn update(&mut self, sample: TempX10) -> Option<TempX10> {
-        if self.count < 5 {
+    /// Discards all samples; the warm-up configuration is kept.
+    pub fn reset(&mut self) {
+        *self = Self {
+            warmup_fallback: self.warmup_fallback,
+            ..Self::default()
+        };
+    }
+
+    /// Returns `Some(filtered)` only once the window is full (or during
+    /// warm-up, see [`Filter::with_warmup`]).
+    pub fn update(&mut self, sample: T) -> Option<T> {
+        if self.count < N {
             self.window[self.index] = sample;
-            self.sum += sample as i32;
-            self.index = (self.index + 1) % 5;
+            self.sum += sample.widen();
+            self.index = (self.index + 1) % N;
             self.count += 1;
-            return None;
+            return self.warmup_output();
         }
 
         let old = self.window[self.index];
-        self.sum -= old as i32;
+        self.sum -= old.widen();
         self.window[self.index] = sample;
-        self.sum += sample as i32;
-        self.index = (self.index + 1) % 5;
+        self.sum += sample.widen();
+        self.index = (self.index + 1) % N;
 
-        Some((self.sum / 5) as TempX10)
+        Some(saturating_average(self.sum, N))
     }
-}
 
-/// REQ_FUNC_003/004: threshold + hysteresis state machine.
-pub struct StateMachine {
-    high_x10: TempX10,
-    low_x10: TempX10,
-    pub state: State,
-}
+    /// Records a skipped conversion: the slot is filled with a repeat of the
+    /// previous sample (sample-and-hold), so the window keeps one slot per
+    /// sample period and downstream rates stay time-correct.
+    ///
+    /// Before any real sample there is nothing to hold; the filter is left
+    /// unchanged and `None` is returned.
+    pub fn update_missing(&mut self) -> Option<T> {
+        let held = self.last_sample()?;
+        self.update(held)
+    }
 
-impl StateMachine {
-    pub fn new(high_x10: TempX10, low_x10: TempX10) -> Self {
-        Self {
-            high_x10,
-            low_x10,
-            state: State::Safe,
-        }
+    /// Like [`Filter::update`], but also echoes the ingested sample so raw
+    /// and filtered values can be logged together.
+    pub fn update_tee(&mut self, sample: T) -> (T, Option<T>) {
+        (sample, self.update(sample))
     }
 
-    pub fn evaluate(&mut self, filtered_temp_x10: TempX10) -> State {
-        match self.state {
-            State::Safe => {
-                if filtered_temp_x10 >= self.high_x10 {
-                    self.state = State::Unsafe;
-                }
-            }
-            State::Unsafe => {
-                if filtered_temp_x10 <= self.low_x10 {
+    /// Average of the current window, or `None` until it is full.
+    pub fn current(&self) -> Option<T> {
+        (self.count == N).then(|| saturating_average(self.sum, N))
+    }
+
+    /// Continues from `other`'s window, e.g. on a handoff between
+    /// redundant channels, instead of priming from empty. Only this
+    /// filter's warm-up configuration is kept.
+    ///
+    /// Assumes both sensors measure the same physical quantity at the same
+    /// point; seeding across differently placed sensors carries one
+    /// sensor's reading into the other's average for `N` samples.
+    pub fn seed_from(&mut self, other: &Filter<N, T>) {
+        *self = Self {
+            warmup_fallback: self.warmup_fallback,
+            ..other.clone()
+        };
+    }
+
+    /// Oldest sample, which the next [`Filter::update`] will drop, or
+    /// `None` until the window is full.
+    pub fn next_to_evict(&self) -> Option<T> {
+        (self.count == N && N > 0).then(|| self.window[self.index])
+    }
+
+    /// Samples currently in the window (`N` once full).
+    pub fn sample_count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of samples averaged (`N`).
+    pub const fn window_len(&self) -> usize {
+        N
+    }
+
+    /// Samples after a step until the boxcar output has covered
+    /// `within_pct` percent of the step height.
+    ///
+    /// With `k` post-step samples in the window the output is `k/N` of the
+    /// step, so this is `ceil(within_pct * N / 100)`, capped at `N`. A
+    /// property of `N` alone; runtime state is ignored.
+    pub const fn settling_samples(&self, within_pct: u8) -> usize {
+        let pct = if within_pct > 100 {
+            100
+        } else {
+            within_pct as usize
+        };
+        (pct * N).div_ceil(100)
+    }
+
+    /// Lag of the output behind the newest sample: the window centre,
+    /// `(N - 1) / 2` samples. For even `N` the centre falls between two
+    /// samples and is rounded down (N=4 gives 1, not 1.5).
+    pub const fn group_delay_samples(&self) -> u32 {
+        (N.saturating_sub(1) / 2) as u32
+    }
+
+    /// DC gain scaled by 1000; the boxcar weights sum to one, so this is
+    /// always 1000.
+    pub const fn dc_gain_x1000() -> u32 {
+        1000
+    }
+
+    /// Impulse response, oldest tap first, each `1/N` scaled by 1000 and
+    /// truncated: the taps sum to 1000 less `1000 % N` (999 for `N = 3`).
+    pub const fn impulse_response_x1000() -> [u16; N] {
+        [(1000 / N) as u16; N]
+    }
+
+    /// White-noise RMS reduction `sqrt(N)` scaled by 100, rounded down.
+    pub fn noise_reduction_x100() -> u32 {
+        isqrt(N as u64 * 10_000) as u32
+    }
+
+    /// Most recently ingested sample, or `None` before the first update.
+    pub fn last_sample(&self) -> Option<T> {
+        self.sample_at(0)
+    }
+
+    /// Sample `age` updates back (0 = newest, `sample_count() - 1` =
+    /// oldest), or `None` if it is no longer (or not yet) in the window.
+    pub fn sample_at(&self, age: usize) -> Option<T> {
+        (age < self.count).then(|| self.window[(self.index + N - 1 - age) % N])
+    }
+
+    /// Samples currently in the window, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.count)
+            .rev()
+            .filter_map(move |age| self.sample_at(age))
+    }
+
+    fn warmup_output(&self) -> Option<T> {
+        let fallback: i64 = self.warmup_fallback?.into();
+        let n = N as i64;
+        let k = self.count as i64;
+        // fallback * (n - k) / n + (sum / k) * k / n, on a common denominator.
+        let blended = (fallback * (n - k) + self.sum.into()) / n;
+        Some(T::saturating_from(blended))
+    }
+}
+
+impl<const N: usize> Filter<N> {
+    /// Like [`Filter::update`], but returns the exact average as
+    /// `whole + remainder / N` (denominator `N`, `0 <= remainder < N`).
+    ///
+    /// `whole` is the floor of the average, so for negative non-integer
+    /// averages it is one below `update`'s truncated result.
+    pub fn update_fractional(&mut self, sample: TempX10) -> Option<(TempX10, u8)> {
+        const { assert!(N <= 256, "remainder must fit in u8") };
+        self.update(sample)?;
+        let n = N as i32;
+        Some((
+            self.sum.div_euclid(n) as TempX10,
+            self.sum.rem_euclid(n) as u8,
+        ))
+    }
+
+    /// RMS of the AC component (samples minus the window mean), rounded
+    /// down, or `None` until the window is full.
+    pub fn ac_rms_x10(&self) -> Option<TempX10> {
+        if self.count < N {
+            return None;
+        }
+        // N * sum((x - mean)^2) == N * sum(x^2) - sum(x)^2, so
+        // rms = sqrt(N * sum(x^2) - sum(x)^2) / N without fractional means.
+        let n = N as i64;
+        let sum: i64 = self.window.iter().map(|&x| x as i64).sum();
+        let sum_sq: i64 = self.window.iter().map(|&x| x as i64 * x as i64).sum();
+        let spread = (n * sum_sq - sum * sum) as u64;
+        Some((isqrt(spread) / N as u64) as TempX10)
+    }
+
+    /// Least-squares slope over the window in 0.01°C per sample (rounded),
+    /// or `None` until the window is full. Always `None` for `N < 2`.
+    pub fn trend_slope_x100(&self) -> Option<i32> {
+        if N < 2 || self.count < N {
+            return None;
+        }
+        // With x = 0..N oldest first: sum(x) = N(N-1)/2 and
+        // N * sum(x^2) - sum(x)^2 = N^2 (N^2 - 1) / 12.
+        let n = N as i64;
+        let sum_x = n * (n - 1) / 2;
+        let denominator = n * n * (n * n - 1) / 12;
+        let (sum_y, sum_xy) = (0..N).fold((0i64, 0i64), |(sy, sxy), x| {
+            let y = self.window[(self.index + x) % N] as i64;
+            (sy + y, sxy + x as i64 * y)
+        });
+        // The x10 temperature scale times 10 gives 0.01°C units.
+        let slope = div_round(10 * (n * sum_xy - sum_x * sum_y), denominator);
+        Some(slope.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    /// Whether the window's peak-to-peak spread is within `tolerance_x10`,
+    /// i.e. the input has settled; `None` until the window is full.
+    pub fn has_settled(&self, tolerance_x10: TempX10) -> Option<bool> {
+        if self.count < N {
+            return None;
+        }
+        let min = *self.window.iter().min()? as i32;
+        let max = *self.window.iter().max()? as i32;
+        Some(max - min <= tolerance_x10 as i32)
+    }
+
+    /// Sum of the samples currently in the window, for combining partial
+    /// averages across nodes as `sum(raw_sum) / sum(sample_count)`.
+    pub fn raw_sum(&self) -> i32 {
+        self.sum
+    }
+
+    /// Overwrites the running sum. Only for fault-injection tests.
+    #[doc(hidden)]
+    pub fn set_sum_for_test(&mut self, sum: i32) {
+        self.sum = sum;
+    }
+}
+
+impl<const N: usize, T: Temperature> Default for Filter<N, T> {
+    fn default() -> Self {
+        const { assert!(N > 0, "filter window must not be empty") };
+        Self {
+            window: [T::default(); N],
+            count: 0,
+            index: 0,
+            sum: T::Sum::default(),
+            warmup_fallback: None,
+        }
+    }
+}
+
+/// `sum / n`, saturated to `T` rather than wrapping.
+fn saturating_average<T: Temperature>(sum: T::Sum, n: usize) -> T {
+    T::saturating_from(sum.into() / n as i64)
+}
+
+/// Integer square root, rounded down.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from an initial guess >= sqrt(n); converges from above.
+    let mut x = 1u64 << (64 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Moving average whose window length is chosen at runtime, up to `MAX`.
+///
+/// Mirrors the [`Filter`] contract (`None` until the window is full) without
+/// allocating; storage for `MAX` samples is reserved inline.
+#[derive(Clone)]
+pub struct DynFilter<const MAX: usize> {
+    window: [TempX10; MAX],
+    len: usize,
+    count: usize,
+    index: usize,
+    sum: i32,
+}
+
+impl<const MAX: usize> DynFilter<MAX> {
+    /// Returns `None` unless `1 <= window <= MAX`.
+    pub fn new(window: usize) -> Option<Self> {
+        if window == 0 || window > MAX {
+            return None;
+        }
+        Some(Self {
+            window: [0; MAX],
+            len: window,
+            count: 0,
+            index: 0,
+            sum: 0,
+        })
+    }
+
+    /// Effective window length.
+    pub fn window_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `Some(filtered)` only once the window is full.
+    pub fn update(&mut self, sample: TempX10) -> Option<TempX10> {
+        if self.count < self.len {
+            self.window[self.index] = sample;
+            self.sum += sample as i32;
+            self.index = (self.index + 1) % self.len;
+            self.count += 1;
+            return None;
+        }
+
+        let old = self.window[self.index];
+        self.sum -= old as i32;
+        self.window[self.index] = sample;
+        self.sum += sample as i32;
+        self.index = (self.index + 1) % self.len;
+
+        Some(saturating_average(self.sum, self.len))
+    }
+}
+
+/// Moving average of raw ADC codes, for averaging before a nonlinear
+/// conversion. Mirrors the [`Filter`] contract (`None` until the window is
+/// full); the average is truncated like [`Filter`]'s.
+///
+/// For a linear characteristic averaging codes then converting equals
+/// converting then averaging (up to rounding). For a nonlinear one (e.g.
+/// [`ThermistorTable`]) it does not: the conversion of the mean code
+/// differs from the mean of the converted values, and noise around a bend
+/// of the curve biases the latter. Filter in the domain where the noise is
+/// additive, usually the ADC code.
+#[derive(Clone)]
+pub struct RawFilter<const N: usize = 5> {
+    window: [u16; N],
+    count: usize,
+    index: usize,
+    sum: u32,
+}
+
+impl<const N: usize> RawFilter<N> {
+    pub fn new() -> Self {
+        const { assert!(N > 0, "filter window must not be empty") };
+        Self {
+            window: [0; N],
+            count: 0,
+            index: 0,
+            sum: 0,
+        }
+    }
+
+    pub fn update(&mut self, adc_counts: u16) -> Option<u16> {
+        let full = self.count == N;
+        self.sum = self.sum - self.window[self.index] as u32 + adc_counts as u32;
+        self.window[self.index] = adc_counts;
+        self.index = (self.index + 1) % N;
+        self.count = (self.count + 1).min(N);
+        full.then(|| (self.sum / N as u32) as u16)
+    }
+
+    /// Sample-and-hold like [`Filter::update_missing`]: repeats the newest
+    /// code, or returns `None` without changing anything if there is none.
+    pub fn update_missing(&mut self) -> Option<u16> {
+        if self.count == 0 {
+            return None;
+        }
+        self.update(self.window[(self.index + N - 1) % N])
+    }
+}
+
+impl<const N: usize> Default for RawFilter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dual-window moving average: follows the `SHORT` average through
+/// transients and the `LONG` average when steady.
+///
+/// While the two averages differ by more than `deviation_x10` the output is
+/// the short-window value, otherwise the long-window one. Until the long
+/// window fills, the short-window value is used.
+#[derive(Clone)]
+pub struct AdaptiveFilter<const SHORT: usize, const LONG: usize> {
+    short: Filter<SHORT>,
+    long: Filter<LONG>,
+    deviation_x10: TempX10,
+}
+
+impl<const SHORT: usize, const LONG: usize> AdaptiveFilter<SHORT, LONG> {
+    pub fn new(deviation_x10: TempX10) -> Self {
+        Self {
+            short: Filter::default(),
+            long: Filter::default(),
+            deviation_x10,
+        }
+    }
+
+    /// Returns `None` until the short window is full.
+    pub fn update(&mut self, sample: TempX10) -> Option<TempX10> {
+        let long = self.long.update(sample);
+        let short = self.short.update(sample)?;
+        match long {
+            Some(long) if (short as i32 - long as i32).abs() <= self.deviation_x10 as i32 => {
+                Some(long)
+            }
+            _ => Some(short),
+        }
+    }
+}
+
+/// Moving average over the last `N` samples excluding the window's minimum
+/// and maximum, rejecting one outlier on each tail. Mirrors the [`Filter`]
+/// contract (`None` until the window is full).
+#[derive(Clone)]
+pub struct TrimmedMeanFilter<const N: usize> {
+    inner: Filter<N>,
+}
+
+impl<const N: usize> TrimmedMeanFilter<N> {
+    pub fn new() -> Self {
+        const { assert!(N >= 3, "trimmed mean needs at least 3 samples") };
+        Self {
+            inner: Filter::default(),
+        }
+    }
+
+    pub fn update(&mut self, sample: TempX10) -> Option<TempX10> {
+        self.inner.update(sample)?;
+        let window = &self.inner.window;
+        let min = *window.iter().min()? as i32;
+        let max = *window.iter().max()? as i32;
+        Some(saturating_average(self.inner.sum - min - max, N - 2))
+    }
+}
+
+impl<const N: usize> Default for TrimmedMeanFilter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moving average over the last `N` samples, each weighted by the inverse of
+/// its variance (the minimum-variance blend of independent readings).
+/// Mirrors the [`Filter`] contract (`None` until the window is full).
+///
+/// Weights are `2^32 / variance`, rounded to nearest in the result. A
+/// zero variance is exact: while any is in the window, the output is the
+/// plain mean of the zero-variance samples and the others are ignored.
+#[derive(Clone)]
+pub struct InverseVarianceFilter<const N: usize = 5> {
+    window: [(TempX10, u16); N],
+    count: usize,
+    index: usize,
+}
+
+impl<const N: usize> InverseVarianceFilter<N> {
+    pub fn new() -> Self {
+        const { assert!(N > 0, "filter window must not be empty") };
+        Self {
+            window: [(0, 0); N],
+            count: 0,
+            index: 0,
+        }
+    }
+
+    pub fn update(&mut self, sample: TempX10, variance: u16) -> Option<TempX10> {
+        let full = self.count == N;
+        self.window[self.index] = (sample, variance);
+        self.index = (self.index + 1) % N;
+        self.count = (self.count + 1).min(N);
+        if !full {
+            return None;
+        }
+
+        let exact = self.window.iter().filter(|&&(_, variance)| variance == 0);
+        let (sum, weights) = if exact.clone().next().is_some() {
+            exact.fold((0i64, 0i64), |(sum, n), &(sample, _)| {
+                (sum + sample as i64, n + 1)
+            })
+        } else {
+            self.window
+                .iter()
+                .fold((0i64, 0i64), |(sum, total), &(sample, variance)| {
+                    let weight = (1i64 << 32) / variance as i64;
+                    (sum + sample as i64 * weight, total + weight)
+                })
+        };
+        Some(div_round(sum, weights) as TempX10)
+    }
+}
+
+impl<const N: usize> Default for InverseVarianceFilter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One processing step of a [`FilterChain`].
+pub trait Stage {
+    /// Processes one sample; `None` stops the chain for this sample.
+    fn process(&mut self, temp_x10: TempX10) -> Option<TempX10>;
+}
+
+impl<const N: usize> Stage for Filter<N> {
+    fn process(&mut self, temp_x10: TempX10) -> Option<TempX10> {
+        self.update(temp_x10)
+    }
+}
+
+/// Rejects physically implausible jumps between consecutive readings.
+pub struct PlausibilityGate {
+    max_jump_x10: i16,
+    last_accepted: Option<TempX10>,
+}
+
+impl PlausibilityGate {
+    pub fn new(max_jump_x10: i16) -> Self {
+        Self {
+            max_jump_x10,
+            last_accepted: None,
+        }
+    }
+
+    /// Passes `temp_x10` through unless it differs from the last accepted
+    /// value by more than `max_jump_x10`.
+    ///
+    /// The first sample is always accepted. A rejected sample does not move
+    /// the reference, so a glitch cannot drag the gate along with it.
+    pub fn accept(&mut self, temp_x10: TempX10) -> Option<TempX10> {
+        if let Some(last) = self.last_accepted {
+            let jump = (temp_x10 as i32 - last as i32).abs();
+            if jump > self.max_jump_x10 as i32 {
+                return None;
+            }
+        }
+        self.last_accepted = Some(temp_x10);
+        Some(temp_x10)
+    }
+}
+
+impl Stage for PlausibilityGate {
+    fn process(&mut self, temp_x10: TempX10) -> Option<TempX10> {
+        self.accept(temp_x10)
+    }
+}
+
+/// Ordered, user-assembled sequence of [`Stage`]s.
+#[derive(Default)]
+pub struct FilterChain {
+    stages: Vec<Box<dyn Stage>>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a stage; samples pass through stages in insertion order.
+    pub fn stage(mut self, stage: impl Stage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Threads `temp_x10` through every stage, short-circuiting on `None`.
+    ///
+    /// Stages after the one returning `None` do not see the sample.
+    pub fn update(&mut self, temp_x10: TempX10) -> Option<TempX10> {
+        self.stages
+            .iter_mut()
+            .try_fold(temp_x10, |t, stage| stage.process(t))
+    }
+}
+
+/// Estimates the lag (in samples) of `filtered` relative to `raw`.
+///
+/// Both slices must be aligned sample-for-sample. The lag is the shift in
+/// `0..=max_lag` at which the mean-removed cross-correlation peaks; each
+/// shift is normalized by its overlap length so larger lags are not
+/// penalized. Returns `None` if the slices differ in length or are too short
+/// to evaluate every lag. Intended as an offline tuning aid, not for the
+/// control loop.
+pub fn estimate_lag(raw: &[TempX10], filtered: &[TempX10], max_lag: usize) -> Option<usize> {
+    if raw.len() != filtered.len() || raw.len() <= max_lag {
+        return None;
+    }
+
+    let n = raw.len() as i64;
+    let mean_raw = raw.iter().map(|&v| v as i64).sum::<i64>() / n;
+    let mean_filtered = filtered.iter().map(|&v| v as i64).sum::<i64>() / n;
+
+    let mut best_lag = 0;
+    let mut best_score = i64::MIN;
+    for lag in 0..=max_lag {
+        let overlap = raw.len() - lag;
+        let corr: i64 = raw[..overlap]
+            .iter()
+            .zip(&filtered[lag..])
+            .map(|(&r, &f)| (r as i64 - mean_raw) * (f as i64 - mean_filtered))
+            .sum();
+        let score = corr / overlap as i64;
+        if score > best_score {
+            best_score = score;
+            best_lag = lag;
+        }
+    }
+
+    Some(best_lag)
+}
+
+/// Threshold validated against the sensor domain
+/// (`TEMP_MIN_X10..=TEMP_MAX_X10`), for [`StateMachine::from_thresholds`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Threshold(TempX10);
+
+impl Threshold {
+    /// Returns `None` outside the sensor domain.
+    pub const fn new(x10: TempX10) -> Option<Self> {
+        if x10 < TEMP_MIN_X10 || x10 > TEMP_MAX_X10 {
+            return None;
+        }
+        Some(Self(x10))
+    }
+
+    /// Skips validation, for trusted constants; out-of-domain values fail
+    /// const evaluation or a debug assertion.
+    pub const fn new_unchecked(x10: TempX10) -> Self {
+        debug_assert!(x10 >= TEMP_MIN_X10 && x10 <= TEMP_MAX_X10);
+        Self(x10)
+    }
+
+    pub const fn get(self) -> TempX10 {
+        self.0
+    }
+}
+
+/// Whether a temperature exactly at the trip threshold trips.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TripEdge {
+    /// Trip at `filtered >= high_x10` (REQ_FUNC_003).
+    #[default]
+    Inclusive,
+    /// Trip at `filtered > high_x10`.
+    Exclusive,
+}
+
+/// REQ_FUNC_003/004: threshold + hysteresis state machine.
+#[derive(Clone)]
+pub struct StateMachine<T: Temperature = TempX10> {
+    high: T,
+    low: T,
+    trip_edge: TripEdge,
+    debounce: u32,
+    over_count: u32,
+    recovery_confirm: u32,
+    under_count: u32,
+    latching: bool,
+    latched: bool,
+    transitions: u32,
+    frozen: bool,
+    pub state: State,
+}
+
+impl<T: Temperature> StateMachine<T> {
+    /// [`StateMachine::new`] for any [`Temperature`] scale, e.g.
+    /// `StateMachine::<TempX100>::with_thresholds(10_000, 9_500)`.
+    pub fn with_thresholds(high: T, low: T) -> Self {
+        Self {
+            high,
+            low,
+            trip_edge: TripEdge::Inclusive,
+            debounce: 1,
+            over_count: 0,
+            recovery_confirm: 0,
+            under_count: 0,
+            latching: false,
+            latched: false,
+            transitions: 0,
+            frozen: false,
+            state: State::Safe,
+        }
+    }
+
+    /// Selects whether the trip threshold itself trips; see [`TripEdge`].
+    /// Recovery at the recovery threshold stays inclusive.
+    pub fn with_trip_edge(mut self, trip_edge: TripEdge) -> Self {
+        self.trip_edge = trip_edge;
+        self
+    }
+
+    pub fn trip_edge(&self) -> TripEdge {
+        self.trip_edge
+    }
+
+    pub fn evaluate(&mut self, filtered: T) -> State {
+        if self.frozen {
+            return self.state;
+        }
+        let before = self.state;
+        match self.state {
+            State::Safe => {
+                let trips = match self.trip_edge {
+                    TripEdge::Inclusive => filtered >= self.high,
+                    TripEdge::Exclusive => filtered > self.high,
+                };
+                self.over_count = if trips {
+                    self.over_count.saturating_add(1)
+                } else {
+                    0
+                };
+                if self.over_count >= self.debounce {
+                    self.over_count = 0;
+                    self.latched = self.latching;
+                    self.state = State::Unsafe;
+                }
+            }
+            State::Unsafe => {
+                let recovers = !self.latched && filtered <= self.low;
+                self.under_count = if recovers {
+                    self.under_count.saturating_add(1)
+                } else {
+                    0
+                };
+                if self.under_count > self.recovery_confirm {
+                    self.under_count = 0;
                     self.state = State::Safe;
                 }
             }
         }
+        if self.state != before {
+            self.transitions = self.transitions.saturating_add(1);
+        }
         self.state
     }
+
+    /// Maintenance mode: while frozen, [`StateMachine::evaluate`] ignores its
+    /// input and returns the current state, so a disconnected probe cannot
+    /// trip or recover it. Debounce and recovery counts are kept as they were.
+    ///
+    /// Not for normal operation: a frozen machine does not protect anything.
+    /// Freeze only in a known-safe condition (process stopped, state `Safe`
+    /// confirmed), and unfreeze before returning to service.
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Every edge of this configuration as `(from, condition, to)`, with the
+    /// condition on the filtered input spelled out, e.g.
+    /// `(Safe, "filtered >= 1000", Unsafe)`.
+    pub fn transition_table(&self) -> [(State, String, State); 2] {
+        let op = match self.trip_edge {
+            TripEdge::Inclusive => ">=",
+            TripEdge::Exclusive => ">",
+        };
+        let mut trip = format!("filtered {op} {}", self.high);
+        if self.debounce > 1 {
+            trip += &format!(" for {} consecutive samples", self.debounce);
+        }
+        let mut recover = format!("filtered <= {}", self.low);
+        if self.recovery_confirm > 0 {
+            recover += &format!(" for {} consecutive samples", self.recovery_confirm + 1);
+        }
+        if self.latching {
+            recover += " after clear_latch";
+        }
+        [
+            (State::Safe, trip, State::Unsafe),
+            (State::Unsafe, recover, State::Safe),
+        ]
+    }
+
+    /// Hysteresis band, recovery threshold to trip threshold inclusive.
+    pub fn band(&self) -> RangeInclusive<T> {
+        self.low..=self.high
+    }
+
+    /// True if `temp` lies in [`StateMachine::band`], boundaries
+    /// included.
+    pub fn contains_in_band(&self, temp: T) -> bool {
+        self.band().contains(&temp)
+    }
+
+    /// Whether a confirmed trip is being held, see
+    /// [`StateMachineBuilder::latch`].
+    pub fn is_latched(&self) -> bool {
+        self.latched
+    }
+
+    /// Releases a held trip. The state stays `Unsafe`; the next
+    /// [`StateMachine::evaluate`] at or below the recovery threshold
+    /// recovers as usual.
+    pub fn clear_latch(&mut self) {
+        self.latched = false;
+    }
+
+    /// Forces `Safe` and clears the latch and the debounce and recovery
+    /// counts; thresholds and options are kept. Not counted as a transition.
+    ///
+    /// Nothing is re-checked: if the input is still at the trip threshold,
+    /// the next [`StateMachine::evaluate`] trips again (after the debounce).
+    pub fn reset_to_safe(&mut self) {
+        self.state = State::Safe;
+        self.latched = false;
+        self.over_count = 0;
+        self.under_count = 0;
+    }
+
+    /// Safe/Unsafe edges seen by [`StateMachine::evaluate`] since
+    /// construction or the last reset; saturates at `u32::MAX`.
+    pub fn transition_count(&self) -> u32 {
+        self.transitions
+    }
+
+    pub fn reset_transition_count(&mut self) {
+        self.transitions = 0;
+    }
 }
+
+impl StateMachine {
+    pub fn new(high_x10: TempX10, low_x10: TempX10) -> Self {
+        Self::with_thresholds(high_x10, low_x10)
+    }
+
+    /// Starts a [`StateMachineBuilder`] for debounce and latch options.
+    pub fn builder(high_x10: TempX10, low_x10: TempX10) -> StateMachineBuilder {
+        StateMachineBuilder::new(high_x10, low_x10)
+    }
+
+    /// Like [`StateMachine::new`], with both thresholds known to lie in the
+    /// sensor domain.
+    pub fn from_thresholds(high: Threshold, low: Threshold) -> Self {
+        Self::new(high.get(), low.get())
+    }
+
+    /// Derives `low_x10` as `high_x10 - high_x10 * hysteresis_pct / 100`.
+    ///
+    /// The product is truncated toward zero, so for a positive trip point the
+    /// band is rounded down to whole 0.1°C (e.g. 5% of 99.9°C is 4.9°C, not
+    /// 4.995°C). Fails if the derived band is empty: a 0% hysteresis, or one
+    /// that rounds to zero, would let the output chatter at the trip point.
+    /// A percentage above 100 is [`ConfigError::OutOfRange`].
+    pub fn from_high_and_hysteresis_pct(
+        high_x10: TempX10,
+        hysteresis_pct: u8,
+    ) -> Result<Self, ConfigError> {
+        if hysteresis_pct > 100 {
+            return Err(ConfigError::OutOfRange);
+        }
+        let band = high_x10 as i32 * hysteresis_pct as i32 / 100;
+        let low_x10 = high_x10 as i32 - band;
+        if low_x10 >= high_x10 as i32 {
+            return Err(ConfigError::EmptyHysteresis);
+        }
+        let low_x10 = TempX10::try_from(low_x10).map_err(|_| ConfigError::OutOfRange)?;
+        Ok(Self::new(high_x10, low_x10))
+    }
+
+    /// Thermostat-style configuration: trip at `setpoint + band / 2`,
+    /// recover at `setpoint - band / 2`.
+    ///
+    /// For an odd `band_x10` the extra 0.1°C goes to the trip side (a 4.5°C
+    /// band around 60.0°C gives 62.3°C / 57.8°C), so `high - low` is always
+    /// exactly `band_x10`. Fails unless `band_x10 > 0`.
+    pub fn from_setpoint_band(
+        setpoint_x10: TempX10,
+        band_x10: TempX10,
+    ) -> Result<Self, ConfigError> {
+        if band_x10 <= 0 {
+            return Err(ConfigError::EmptyHysteresis);
+        }
+        let (setpoint, band) = (setpoint_x10 as i32, band_x10 as i32);
+        let high =
+            TempX10::try_from(setpoint + (band + 1) / 2).map_err(|_| ConfigError::OutOfRange)?;
+        let low = TempX10::try_from(setpoint - band / 2).map_err(|_| ConfigError::OutOfRange)?;
+        Ok(Self::new(high, low))
+    }
+
+    /// Asymmetric band around `setpoint_x10`: trip at
+    /// `setpoint + rise_band`, recover at `setpoint - fall_band`, for a
+    /// process that heats fast and cools slowly. Fails unless both bands are
+    /// positive, and with [`ConfigError::OutOfRange`] if a threshold does
+    /// not fit in `TempX10`.
+    pub fn from_setpoint_asymmetric(
+        setpoint_x10: TempX10,
+        rise_band_x10: TempX10,
+        fall_band_x10: TempX10,
+    ) -> Result<Self, ConfigError> {
+        if rise_band_x10 <= 0 || fall_band_x10 <= 0 {
+            return Err(ConfigError::EmptyHysteresis);
+        }
+        let setpoint = setpoint_x10 as i32;
+        let high = TempX10::try_from(setpoint + rise_band_x10 as i32)
+            .map_err(|_| ConfigError::OutOfRange)?;
+        let low = TempX10::try_from(setpoint - fall_band_x10 as i32)
+            .map_err(|_| ConfigError::OutOfRange)?;
+        Ok(Self::new(high, low))
+    }
+
+    /// Thresholds given in 0.1°F, converted with
+    /// [`fahrenheit_x10_to_celsius_x10`]. Fails if the converted band is
+    /// empty; 0.1°F apart can round to the same 0.1°C.
+    pub fn from_fahrenheit(high_f_x10: i16, low_f_x10: i16) -> Result<Self, ConfigError> {
+        let high_x10 = fahrenheit_x10_to_celsius_x10(high_f_x10);
+        let low_x10 = fahrenheit_x10_to_celsius_x10(low_f_x10);
+        if low_x10 >= high_x10 {
+            return Err(ConfigError::EmptyHysteresis);
+        }
+        Ok(Self::new(high_x10, low_x10))
+    }
+
+    /// Like [`StateMachine::new`], but checked against what `sensor` can
+    /// report: a trip point above [`LinearSensor::max_x10`] could never fire,
+    /// and a recovery point below [`LinearSensor::min_x10`] could never
+    /// clear. Both are [`ConfigError::OutOfRange`].
+    pub fn within_sensor(
+        sensor: &LinearSensor,
+        high_x10: TempX10,
+        low_x10: TempX10,
+    ) -> Result<Self, ConfigError> {
+        if low_x10 >= high_x10 {
+            return Err(ConfigError::EmptyHysteresis);
+        }
+        if high_x10 > sensor.max_x10() || low_x10 < sensor.min_x10() {
+            return Err(ConfigError::OutOfRange);
+        }
+        Ok(Self::new(high_x10, low_x10))
+    }
+
+    /// Trip threshold (`Safe` -> `Unsafe` at or above, or strictly above
+    /// with [`TripEdge::Exclusive`]).
+    pub fn high_x10(&self) -> TempX10 {
+        self.high
+    }
+
+    /// Recovery threshold (`Unsafe` -> `Safe` at or below).
+    pub fn low_x10(&self) -> TempX10 {
+        self.low
+    }
+
+    /// Thresholds and options, without the runtime state.
+    pub fn config(&self) -> StateMachineConfig {
+        StateMachineConfig {
+            high_x10: self.high,
+            low_x10: self.low,
+            trip_edge: self.trip_edge,
+            debounce: self.debounce,
+            recovery_confirm: self.recovery_confirm,
+            latching: self.latching,
+        }
+    }
+
+    /// Rule of thumb for band sizing: true if the hysteresis band is wider
+    /// than the peak-to-peak noise, `high_x10 - low_x10 > 2 * amplitude`.
+    pub fn is_chatter_safe(&self, noise_amplitude_x10: TempX10) -> bool {
+        self.high as i32 - self.low as i32 > 2 * noise_amplitude_x10 as i32
+    }
+
+    /// Headroom to the next transition: `high_x10 - filtered` while `Safe`,
+    /// `filtered - low_x10` while `Unsafe`. Negative once the threshold has
+    /// been crossed; saturates to `i16`.
+    pub fn margin_to_trip_x10(&self, filtered_temp_x10: TempX10) -> i16 {
+        let margin = match self.state {
+            State::Safe => self.high as i32 - filtered_temp_x10 as i32,
+            State::Unsafe => filtered_temp_x10 as i32 - self.low as i32,
+        };
+        margin.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+
+    /// Like [`StateMachine::evaluate`], but rejects temperatures outside the
+    /// sensor domain (`TEMP_MIN_X10..=TEMP_MAX_X10`) without changing state.
+    ///
+    /// Such values can only come from an upstream bug (e.g. a raw ADC count
+    /// passed as a temperature) and must not drive the safety decision.
+    pub fn try_evaluate(&mut self, filtered_temp_x10: TempX10) -> Result<State, DomainError> {
+        if !(TEMP_MIN_X10..=TEMP_MAX_X10).contains(&filtered_temp_x10) {
+            return Err(DomainError(filtered_temp_x10));
+        }
+        Ok(self.evaluate(filtered_temp_x10))
+    }
+
+    /// Fixed-size record for flash storage / parameter updates, holding the
+    /// whole machine: configuration, latch and freeze flags, in-progress
+    /// debounce/recovery counts and the transition count.
+    ///
+    /// Layout (little-endian):
+    ///
+    /// | bytes  | field                                              |
+    /// |--------|----------------------------------------------------|
+    /// | 0      | version, [`STATE_MACHINE_RECORD_VERSION`]          |
+    /// | 1..3   | `high_x10` (`i16`)                                 |
+    /// | 3..5   | `low_x10` (`i16`)                                  |
+    /// | 5      | state: `0` = Safe, `1` = Unsafe                    |
+    /// | 6      | trip edge: `0` = Inclusive, `1` = Exclusive        |
+    /// | 7..11  | debounce (`u32`, at least 1)                       |
+    /// | 11..15 | recovery confirm (`u32`)                           |
+    /// | 15     | flags: bit 0 latching, bit 1 latched, bit 2 frozen |
+    /// | 16..20 | consecutive samples toward a trip (`u32`)          |
+    /// | 20..24 | consecutive samples toward recovery (`u32`)        |
+    /// | 24..28 | transition count (`u32`)                           |
+    pub fn to_bytes(&self) -> [u8; STATE_MACHINE_RECORD_LEN] {
+        let mut record = [0; STATE_MACHINE_RECORD_LEN];
+        record[0] = STATE_MACHINE_RECORD_VERSION;
+        record[1..3].copy_from_slice(&self.high.to_le_bytes());
+        record[3..5].copy_from_slice(&self.low.to_le_bytes());
+        record[5] = match self.state {
+            State::Safe => 0,
+            State::Unsafe => 1,
+        };
+        record[6] = match self.trip_edge {
+            TripEdge::Inclusive => 0,
+            TripEdge::Exclusive => 1,
+        };
+        record[7..11].copy_from_slice(&self.debounce.to_le_bytes());
+        record[11..15].copy_from_slice(&self.recovery_confirm.to_le_bytes());
+        record[15] = self.latching as u8 | (self.latched as u8) << 1 | (self.frozen as u8) << 2;
+        record[16..20].copy_from_slice(&self.over_count.to_le_bytes());
+        record[20..24].copy_from_slice(&self.under_count.to_le_bytes());
+        record[24..28].copy_from_slice(&self.transitions.to_le_bytes());
+        record
+    }
+
+    /// Decodes a record written by [`StateMachine::to_bytes`]. Records of
+    /// another version, including the earlier 5-byte layout, are rejected
+    /// rather than restored with default options.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let record: &[u8; STATE_MACHINE_RECORD_LEN] = bytes
+            .try_into()
+            .map_err(|_| DecodeError::Length(bytes.len()))?;
+        if record[0] != STATE_MACHINE_RECORD_VERSION {
+            return Err(DecodeError::Version(record[0]));
+        }
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]])
+        };
+
+        let state = match record[5] {
+            0 => State::Safe,
+            1 => State::Unsafe,
+            other => return Err(DecodeError::State(other)),
+        };
+        let trip_edge = match record[6] {
+            0 => TripEdge::Inclusive,
+            1 => TripEdge::Exclusive,
+            _ => return Err(DecodeError::Field(6)),
+        };
+        let debounce = u32_at(7);
+        if debounce == 0 {
+            return Err(DecodeError::Field(7));
+        }
+        let flags = record[15];
+        if flags & !0b111 != 0 {
+            return Err(DecodeError::Field(15));
+        }
+
+        let mut sm = Self::new(
+            TempX10::from_le_bytes([record[1], record[2]]),
+            TempX10::from_le_bytes([record[3], record[4]]),
+        );
+        sm.state = state;
+        sm.trip_edge = trip_edge;
+        sm.debounce = debounce;
+        sm.recovery_confirm = u32_at(11);
+        sm.latching = flags & 0b001 != 0;
+        sm.latched = flags & 0b010 != 0;
+        sm.frozen = flags & 0b100 != 0;
+        sm.over_count = u32_at(16);
+        sm.under_count = u32_at(20);
+        sm.transitions = u32_at(24);
+        Ok(sm)
+    }
+}
+
+/// M-out-of-`K` (MooK) voter over independent channel states.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VotingStateMachine<const K: usize> {
+    required: usize,
+}
+
+impl<const K: usize> VotingStateMachine<K> {
+    /// Trips when at least `required` of the `K` inputs are `Unsafe`.
+    /// Returns `None` unless `1 <= required <= K`.
+    pub fn new(required: usize) -> Option<Self> {
+        (1..=K).contains(&required).then_some(Self { required })
+    }
+
+    pub fn vote(&self, inputs: &[State; K]) -> State {
+        let unsafe_count = inputs.iter().filter(|&&s| s == State::Unsafe).count();
+        if unsafe_count >= self.required {
+            State::Unsafe
+        } else {
+            State::Safe
+        }
+    }
+}
+
+/// Threshold presets for common use cases, as `(high_x10, low_x10)`.
+///
+/// The trip points come from the cited sources; the 3.0°C recovery bands
+/// are engineering defaults, not taken from those sources. To override
+/// either value, pass the constants to [`StateMachine::new`] selectively.
+pub mod presets {
+    use crate::TempX10;
+
+    /// TSIM requirements: trip at 100.0°C (REQ_FUNC_003), recover at
+    /// 95.0°C (REQ_FUNC_004).
+    pub const TSIM: (TempX10, TempX10) = (1000, 950);
+
+    /// Li-ion charging cutoff at 45.0°C, the upper limit of the standard
+    /// charging range in the JEITA/BAJ guideline "A Guide to the Safe Use
+    /// of Secondary Lithium Ion Batteries in Notebook-type Personal
+    /// Computers" (2007). Recovers at 42.0°C.
+    pub const LI_ION_CHARGE: (TempX10, TempX10) = (450, 420);
+
+    /// Li-ion discharge cutoff at 60.0°C, the typical upper discharge rating
+    /// in cell datasheets (e.g. Panasonic NCR18650B: -20°C..+60°C).
+    /// Recovers at 57.0°C.
+    pub const LI_ION_DISCHARGE: (TempX10, TempX10) = (600, 570);
+}
+
+impl StateMachine {
+    /// See [`presets::TSIM`].
+    pub fn tsim_default() -> Self {
+        Self::new(presets::TSIM.0, presets::TSIM.1)
+    }
+
+    /// See [`presets::LI_ION_CHARGE`].
+    pub fn li_ion_charge() -> Self {
+        Self::new(presets::LI_ION_CHARGE.0, presets::LI_ION_CHARGE.1)
+    }
+
+    /// See [`presets::LI_ION_DISCHARGE`].
+    pub fn li_ion_discharge() -> Self {
+        Self::new(presets::LI_ION_DISCHARGE.0, presets::LI_ION_DISCHARGE.1)
+    }
+}
+
+/// Builder for [`StateMachine`] options beyond the two thresholds, created
+/// by [`StateMachine::builder`].
+///
+/// Debounce and latch compose in order: while `Safe`, the debounce counts
+/// consecutive trip-qualifying samples and any non-qualifying sample
+/// restarts the count; the `n`th in a row trips the machine, and with
+/// latching enabled that confirmed trip is then held through recovery
+/// until [`StateMachine::clear_latch`].
+#[derive(Clone)]
+pub struct StateMachineBuilder<T: Temperature = TempX10> {
+    sm: StateMachine<T>,
+}
+
+impl<T: Temperature> StateMachineBuilder<T> {
+    /// Like [`StateMachine::builder`] for any [`Temperature`] scale.
+    pub fn new(high: T, low: T) -> Self {
+        Self {
+            sm: StateMachine::with_thresholds(high, low),
+        }
+    }
+
+    /// Requires `samples` consecutive samples at the trip threshold to trip;
+    /// 0 and 1 both trip on the first one.
+    pub fn debounce(mut self, samples: u32) -> Self {
+        self.sm.debounce = samples.max(1);
+        self
+    }
+
+    /// After the first sample at or below `low_x10`, requires `samples`
+    /// further consecutive ones before recovering to `Safe`; any sample
+    /// above `low_x10` restarts the wait. 0 (the default) recovers at once.
+    pub fn recovery_confirm(mut self, samples: u32) -> Self {
+        self.sm.recovery_confirm = samples;
+        self
+    }
+
+    /// Holds `Unsafe` after a trip until [`StateMachine::clear_latch`].
+    pub fn latch(mut self, latching: bool) -> Self {
+        self.sm.latching = latching;
+        self
+    }
+
+    pub fn trip_edge(mut self, trip_edge: TripEdge) -> Self {
+        self.sm.trip_edge = trip_edge;
+        self
+    }
+
+    pub fn build(self) -> StateMachine<T> {
+        self.sm
+    }
+}
+
+/// Configuration part of a [`StateMachine`], see [`StateMachine::config`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StateMachineConfig {
+    pub high_x10: TempX10,
+    pub low_x10: TempX10,
+    pub trip_edge: TripEdge,
+    pub debounce: u32,
+    pub recovery_confirm: u32,
+    pub latching: bool,
+}
+
+/// Size of the [`StateMachine::to_bytes`] record.
+pub const STATE_MACHINE_RECORD_LEN: usize = 28;
+
+/// Layout version in byte 0 of the [`StateMachine::to_bytes`] record.
+pub const STATE_MACHINE_RECORD_VERSION: u8 = 1;
+
+/// Error decoding a [`StateMachine`] record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Buffer length (expected [`STATE_MACHINE_RECORD_LEN`]).
+    Length(usize),
+    /// Unknown state discriminant byte.
+    State(u8),
+    /// Record version other than [`STATE_MACHINE_RECORD_VERSION`].
+    Version(u8),
+    /// Invalid value in the field starting at this byte offset.
+    Field(usize),
+}
+
+/// Temperature outside the sensor domain, rejected by
+/// [`StateMachine::try_evaluate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DomainError(pub TempX10);
+
+/// Invalid [`StateMachine`] configuration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The recovery threshold is not below the trip threshold.
+    EmptyHysteresis,
+    /// A parameter is out of its valid range, or a derived threshold does
+    /// not fit in `TempX10`.
+    OutOfRange,
+}
+
+/// What [`Pipeline`] averages in place of a conversion that
+/// [`adc_to_temp_x10_clamped`] reports as clamped.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClampedSamplePolicy {
+    /// Average the clamped +125.0°C like any other sample.
+    #[default]
+    Include,
+    /// Treat it as missing and hold the previous sample, see
+    /// [`Filter::update_missing`].
+    Exclude,
+}
+
+/// End-to-end TSIM chain: ADC conversion -> moving average -> state machine.
+#[derive(Clone)]
+pub struct Pipeline {
+    filter: Filter,
+    machine: StateMachine,
+    invalid_code: Option<u16>,
+    faulted: bool,
+    sample_period_ms: Option<u32>,
+    last_filtered: Option<TempX10>,
+    filtered_delta_x10: Option<i32>,
+    fail_safe_x10: TempX10,
+    filtering: bool,
+    degrade_after: Option<u32>,
+    fault_resets: u32,
+    degraded: bool,
+    primed: bool,
+    raw_filter: Option<RawFilter>,
+    calibration: Option<Calibration>,
+    clamped_policy: ClampedSamplePolicy,
+    converter: Option<Arc<dyn Converter + Send + Sync>>,
+}
+
+/// Result of [`Pipeline::step_outcome`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StepOutcome {
+    pub state: State,
+    /// The state was evaluated on the raw converted temperature, see
+    /// [`Pipeline::with_degrade_after`].
+    pub degraded: bool,
+    /// First filtered output since construction or since a sentinel
+    /// discarded the window. With the 5-sample filter this is the 6th valid
+    /// step: the window fills on the 5th and yields from the next one.
+    pub just_primed: bool,
+}
+
+impl Pipeline {
+    pub fn new(high_x10: TempX10, low_x10: TempX10) -> Self {
+        Self {
+            filter: Filter::new(),
+            machine: StateMachine::new(high_x10, low_x10),
+            invalid_code: None,
+            faulted: false,
+            sample_period_ms: None,
+            last_filtered: None,
+            filtered_delta_x10: None,
+            fail_safe_x10: 1250,
+            filtering: true,
+            degrade_after: None,
+            fault_resets: 0,
+            degraded: false,
+            primed: false,
+            raw_filter: None,
+            calibration: None,
+            clamped_policy: ClampedSamplePolicy::Include,
+            converter: None,
+        }
+    }
+
+    /// Applies `calibration` to every converted temperature before
+    /// filtering.
+    pub fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Replaces the calibration at runtime; the filter window keeps the
+    /// samples calibrated with the previous constants.
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = Some(calibration);
+    }
+
+    /// Averages raw ADC codes and converts the mean, instead of averaging
+    /// converted temperatures; see [`RawFilter`] for when the order
+    /// matters.
+    ///
+    /// Samples already in the temperature window (e.g. from
+    /// [`Pipeline::warm_start`]) carry over as their nearest ADC codes under
+    /// the default conversion; with [`Pipeline::with_converter`] the raw
+    /// window primes from real samples.
+    pub fn with_raw_filtering(mut self) -> Self {
+        let mut raw = RawFilter::new();
+        if self.converter.is_none() {
+            for temp_x10 in self.filter.samples() {
+                let _ = raw.update(temp_x10_to_adc(temp_x10));
+            }
+        }
+        self.raw_filter = Some(raw);
+        self
+    }
+
+    /// Converts ADC codes with `converter` (given the code as its raw
+    /// reading) instead of [`adc_to_temp_x10`], e.g. a nonlinear
+    /// [`ThermistorTable`]. Codes above 4095 still count as clamped for
+    /// [`Pipeline::with_clamped_policy`].
+    ///
+    /// A raw-filter window already holding codes is emptied, since a warm
+    /// start carried it over under the default conversion.
+    pub fn with_converter(mut self, converter: impl Converter + Send + Sync + 'static) -> Self {
+        self.converter = Some(Arc::new(converter));
+        if let Some(raw) = &mut self.raw_filter {
+            if raw.count > 0 {
+                *raw = RawFilter::new();
+                self.primed = false;
+            }
+        }
+        self
+    }
+
+    /// Replaces the default state machine, e.g. with one from
+    /// [`StateMachine::builder`] for debounce or latching.
+    pub fn with_state_machine(mut self, machine: StateMachine) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    /// Sets how clamped conversions enter the average.
+    ///
+    /// Excluding them keeps a corrupt code from dragging the average toward
+    /// the rail (less bias), but a real excursion past the range is then
+    /// seen late or not at all. Including them (the default) reacts to it
+    /// within the filter window at the cost of a biased average. Only the
+    /// averaging is affected; with filtering off the clamped value is
+    /// evaluated either way.
+    pub fn with_clamped_policy(mut self, policy: ClampedSamplePolicy) -> Self {
+        self.clamped_policy = policy;
+        self
+    }
+
+    /// Warm start: fills the filter window with `initial_x10` and evaluates
+    /// the state machine against it, so the first `step` already yields a
+    /// state and a hot start trips at once.
+    ///
+    /// Use after a watchdog or firmware reset while the plant is running,
+    /// with `initial_x10` from a direct sensor read. On power-up from cold,
+    /// use [`Pipeline::new`]: an empty window that primes from real samples.
+    /// `initial_x10` is clamped to the sensor range like a converted sample.
+    pub fn warm_start(initial_x10: TempX10, high_x10: TempX10, low_x10: TempX10) -> Self {
+        let initial_x10 = initial_x10.clamp(TEMP_MIN_X10, TEMP_MAX_X10);
+        let mut pipeline = Self::new(high_x10, low_x10);
+        for _ in 0..pipeline.filter.window_len() {
+            let _ = pipeline.filter.update(initial_x10);
+        }
+        pipeline.last_filtered = Some(initial_x10);
+        pipeline.primed = true;
+        pipeline.machine.evaluate(initial_x10);
+        pipeline
+    }
+
+    /// Enters degraded mode after `resets` consecutive sentinel-induced
+    /// filter resets, i.e. sentinels with no filtered output in between.
+    ///
+    /// In degraded mode, valid samples that find the filter still priming
+    /// are evaluated raw instead of yielding `None`, so a flaky sensor keeps
+    /// producing decisions rather than stalling. Raw values carry the full
+    /// sensor noise and may trip spuriously; consumers should treat
+    /// [`StepOutcome::degraded`] as reduced confidence and lean toward the
+    /// safe action. The mode ends with the next filtered output. Zero
+    /// disables it (the default).
+    pub fn with_degrade_after(mut self, resets: u32) -> Self {
+        self.degrade_after = (resets > 0).then_some(resets);
+        self
+    }
+
+    /// Temperature reported by [`Pipeline::step_fail_safe`] while no valid
+    /// filtered value exists. Defaults to +125.0°C (sensor maximum) so that
+    /// consumers keying off the value, not just the state, also act safe.
+    pub fn with_fail_safe_x10(mut self, fail_safe_x10: TempX10) -> Self {
+        self.fail_safe_x10 = fail_safe_x10;
+        self
+    }
+
+    /// Sets the time between `step` calls so rates can be reported per
+    /// second. A zero period is ignored.
+    pub fn with_sample_period_ms(mut self, period_ms: u32) -> Self {
+        self.sample_period_ms = (period_ms > 0).then_some(period_ms);
+        self
+    }
+
+    /// Treats `code` as the ADC driver's "no valid conversion" sentinel.
+    ///
+    /// The sentinel need not sit at a rail; without this a 0xFFFF code would
+    /// silently clamp to +125.0°C.
+    pub fn with_invalid_code(mut self, code: u16) -> Self {
+        self.invalid_code = Some(code);
+        self
+    }
+
+    /// Converts `adc_counts`, or `None` if it is the configured sentinel.
+    pub fn convert(&self, adc_counts: u16) -> Option<TempX10> {
+        self.convert_clamped(adc_counts)
+            .map(|(temp_x10, _)| temp_x10)
+    }
+
+    /// [`Pipeline::convert`] with the flag of [`adc_to_temp_x10_clamped`].
+    fn convert_clamped(&self, adc_counts: u16) -> Option<(TempX10, bool)> {
+        if self.invalid_code == Some(adc_counts) {
+            return None;
+        }
+        let (linear, clamped) = adc_to_temp_x10_clamped(adc_counts);
+        let temp_x10 = match &self.converter {
+            Some(converter) => converter.convert(adc_counts as i32),
+            None => linear,
+        };
+        Some((temp_x10, clamped))
+    }
+
+    /// Processes one ADC sample.
+    ///
+    /// Returns `None` while the filter is priming or when the sample is the
+    /// invalid sentinel. A sentinel also discards the filter window so the
+    /// average is never built across a gap; see [`Pipeline::is_faulted`].
+    pub fn step(&mut self, adc_counts: u16) -> Option<State> {
+        self.step_outcome(adc_counts).map(|outcome| outcome.state)
+    }
+
+    /// Like [`Pipeline::step`], also reporting whether the decision was
+    /// made in degraded mode.
+    pub fn step_outcome(&mut self, adc_counts: u16) -> Option<StepOutcome> {
+        let Some((temp_x10, clamped)) = self.convert_clamped(adc_counts) else {
+            self.faulted = true;
+            self.discard_history();
+            self.fault_resets = self.fault_resets.saturating_add(1);
+            if self.degrade_after.is_some_and(|m| self.fault_resets >= m) {
+                self.degraded = true;
+            }
+            return None;
+        };
+        self.faulted = false;
+        log_debug!("adc {adc_counts} -> {temp_x10}");
+        let calibration = self.calibration;
+        let calibrate = |t| calibration.map_or(t, |c| c.apply(t));
+        let temp_x10 = calibrate(temp_x10);
+
+        let exclude = clamped && self.clamped_policy == ClampedSamplePolicy::Exclude;
+        let converter = self.converter.as_deref();
+        let convert_mean = |mean: u16| {
+            calibrate(converter.map_or_else(|| adc_to_temp_x10(mean), |c| c.convert(mean as i32)))
+        };
+        let averaged = self.filtering.then(|| match &mut self.raw_filter {
+            Some(raw) if exclude => raw.update_missing().map(convert_mean),
+            Some(raw) => raw.update(adc_counts).map(convert_mean),
+            None if exclude => self.filter.update_missing(),
+            None => self.filter.update(temp_x10),
+        });
+        let ready = match averaged {
+            Some(Some(filtered)) => Some(filtered),
+            None => Some(temp_x10),
+            Some(None) if self.degraded => None,
+            Some(None) => return None,
+        };
+        let just_primed = ready.is_some() && !self.primed;
+        let filtered = match ready {
+            Some(filtered) => {
+                self.fault_resets = 0;
+                self.degraded = false;
+                self.primed = true;
+                filtered
+            }
+            // Degraded: the raw value stands in while the filter primes.
+            None => temp_x10,
+        };
+        // The built-in conversion and calibration both clamp to the sensor
+        // range, so an average of their outputs stays inside it too.
+        debug_assert!(
+            self.converter.is_some() || (TEMP_MIN_X10..=TEMP_MAX_X10).contains(&filtered),
+            "filtered {filtered} outside the sensor range"
+        );
+        self.filtered_delta_x10 = self.last_filtered.map(|last| filtered as i32 - last as i32);
+        self.last_filtered = Some(filtered);
+        let before = self.machine.state;
+        let state = self.machine.evaluate(filtered);
+        if before == State::Safe && state == State::Unsafe {
+            log_warn!("Safe -> Unsafe at filtered {filtered}");
+        }
+        Some(StepOutcome {
+            state,
+            degraded: self.degraded,
+            just_primed,
+        })
+    }
+
+    /// Enables or bypasses the moving average (enabled by default).
+    ///
+    /// With filtering off, `step` evaluates the raw converted temperature:
+    /// no priming and no averaging delay, but also no noise rejection, so
+    /// single noisy samples can trip the state machine. Intended for
+    /// bring-up. Re-enabling restarts the filter from empty so samples from
+    /// before the bypass are never averaged in.
+    pub fn set_filtering(&mut self, enabled: bool) {
+        if enabled && !self.filtering {
+            self.discard_history();
+        }
+        self.filtering = enabled;
+    }
+
+    fn discard_history(&mut self) {
+        self.filter.reset();
+        self.primed = false;
+        if let Some(raw) = &mut self.raw_filter {
+            *raw = RawFilter::new();
+        }
+        self.last_filtered = None;
+        self.filtered_delta_x10 = None;
+    }
+
+    /// Like [`Pipeline::step`], but always yields a value.
+    ///
+    /// While the filter is priming or the sample is invalid, returns the
+    /// configured fail-safe temperature together with `State::Unsafe`, so
+    /// downstream logic defaults to the safe action. The internal state
+    /// machine is not modified by this substitution.
+    pub fn step_fail_safe(&mut self, adc_counts: u16) -> (TempX10, State) {
+        match (self.step(adc_counts), self.last_filtered) {
+            (Some(state), Some(filtered)) => (filtered, state),
+            _ => (self.fail_safe_x10, State::Unsafe),
+        }
+    }
+
+    /// Rate of change of the filtered temperature in 0.1°C per second,
+    /// saturated to `i16`.
+    ///
+    /// Derived from the last two filtered outputs and the sample period;
+    /// `None` without a period or before two filtered outputs exist.
+    pub fn rate_per_second_x10(&self) -> Option<i16> {
+        let period_ms = self.sample_period_ms? as i64;
+        let delta_x10 = self.filtered_delta_x10? as i64;
+        let rate = delta_x10 * 1000 / period_ms;
+        Some(rate.clamp(i16::MIN as i64, i16::MAX as i64) as i16)
+    }
+
+    /// Input temperature at which a steady ramp actually trips.
+    ///
+    /// A boxcar of `N` samples lags a ramp of `r` per sample by
+    /// `(N - 1) / 2` samples, so the filtered value reaches `high_x10` when
+    /// the input is already `high_x10 + (N - 1) * r / 2` (truncated toward
+    /// zero). With filtering bypassed there is no lag and the nominal trip
+    /// point is returned.
+    pub fn effective_trip_x10(&self, ramp_per_sample_x10: i16) -> TempX10 {
+        let lag_samples_x2 = if self.filtering {
+            self.filter.window_len() as i32 - 1
+        } else {
+            0
+        };
+        let correction = lag_samples_x2 * ramp_per_sample_x10 as i32 / 2;
+        let trip = self.machine.high_x10() as i32 + correction;
+        trip.clamp(TempX10::MIN as i32, TempX10::MAX as i32) as TempX10
+    }
+
+    /// Captures the complete pipeline (filter window, state machine state
+    /// and configuration) for a later [`Pipeline::restore`].
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        PipelineSnapshot(self.clone())
+    }
+
+    /// Rolls back to `snap`; subsequent outputs continue that timeline.
+    pub fn restore(&mut self, snap: &PipelineSnapshot) {
+        *self = snap.0.clone();
+    }
+
+    /// Temperature the state machine last evaluated, or `None` before the
+    /// first output and after a sentinel.
+    pub fn last_filtered_x10(&self) -> Option<TempX10> {
+        self.last_filtered
+    }
+
+    /// Upper bound on the samples from an over-temperature step to the
+    /// `Unsafe` declaration, counting the first sample after the step as 1,
+    /// from the live configuration: `N - 1 + debounce` with filtering,
+    /// `debounce` without.
+    ///
+    /// A step that only just clears `high_x10` lifts the average to the trip
+    /// point only once the whole `N`-sample window holds post-step samples;
+    /// the debounce then needs `debounce - 1` more. Larger steps trip sooner,
+    /// after about [`Filter::group_delay_samples`]. Assumes a primed filter.
+    pub fn worst_case_trip_latency_samples(&self) -> u32 {
+        let window_fill = if self.filtering {
+            self.filter.window_len() as u32 - 1
+        } else {
+            0
+        };
+        window_fill + self.machine.config().debounce
+    }
+
+    /// [`StateMachine::reset_to_safe`] on the pipeline's state machine; the
+    /// filter window is kept, so the next `step` evaluates a full average.
+    pub fn reset_to_safe(&mut self) {
+        self.machine.reset_to_safe();
+    }
+
+    /// True while in degraded mode, see [`Pipeline::with_degrade_after`].
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    /// True if the most recent sample was the invalid sentinel.
+    pub fn is_faulted(&self) -> bool {
+        self.faulted
+    }
+
+    pub fn state(&self) -> State {
+        self.machine.state
+    }
+}
+
+/// Opaque checkpoint of a [`Pipeline`], see [`Pipeline::snapshot`].
+#[derive(Clone)]
+pub struct PipelineSnapshot(Pipeline);
+
+/// Settings for the [`Pipeline`] built by [`analyze_trace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PipelineConfig {
+    pub high_x10: TempX10,
+    pub low_x10: TempX10,
+    pub invalid_code: Option<u16>,
+}
+
+impl Default for PipelineConfig {
+    /// TSIM thresholds (REQ_FUNC_003/004), no sentinel.
+    fn default() -> Self {
+        Self {
+            high_x10: presets::TSIM.0,
+            low_x10: presets::TSIM.1,
+            invalid_code: None,
+        }
+    }
+}
+
+impl PipelineConfig {
+    pub fn pipeline(&self) -> Pipeline {
+        let pipeline = Pipeline::new(self.high_x10, self.low_x10);
+        match self.invalid_code {
+            Some(code) => pipeline.with_invalid_code(code),
+            None => pipeline,
+        }
+    }
+}
+
+/// Summary metrics of one [`analyze_trace`] run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceSummary {
+    /// Safe/Unsafe edges.
+    pub transitions: u32,
+    /// Highest filtered temperature, `None` if the filter never primed.
+    pub peak_filtered_x10: Option<TempX10>,
+    /// Samples whose evaluation resulted in `Unsafe`.
+    pub unsafe_samples: usize,
+    pub final_state: State,
+}
+
+/// Runs a recorded ADC trace through a fresh pipeline built from `config`.
+pub fn analyze_trace(adc: &[u16], config: &PipelineConfig) -> TraceSummary {
+    let mut pipeline = config.pipeline();
+    let mut previous = pipeline.state();
+    let mut summary = TraceSummary {
+        transitions: 0,
+        peak_filtered_x10: None,
+        unsafe_samples: 0,
+        final_state: previous,
+    };
+
+    for &code in adc {
+        let Some(state) = pipeline.step(code) else {
+            continue;
+        };
+        if let Some(filtered) = pipeline.last_filtered {
+            summary.peak_filtered_x10 = summary.peak_filtered_x10.max(Some(filtered));
+        }
+        if state != previous {
+            summary.transitions += 1;
+            previous = state;
+        }
+        if state == State::Unsafe {
+            summary.unsafe_samples += 1;
+        }
+    }
+
+    summary.final_state = pipeline.state();
+    summary
+}
+
+/// Thresholds for [`validate_trace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlausibilityLimits {
+    /// Largest believable change between consecutive conversions, as for
+    /// [`PlausibilityGate`].
+    pub max_jump_x10: i16,
+    /// Consecutive same-rail codes that count as a stuck sensor, as for
+    /// [`SaturationMonitor`].
+    pub rail_run: u32,
+}
+
+/// Problem found by [`validate_trace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceIssue {
+    /// Sample index in the trace.
+    pub index: usize,
+    pub kind: TraceIssueKind,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceIssueKind {
+    /// Code above 4095.
+    OutOfRange(u16),
+    /// Conversion jumped from the last plausible one by more than
+    /// `max_jump_x10`.
+    Jump { from_x10: TempX10, to_x10: TempX10 },
+    /// A rail run reached `rail_run` samples at this index; reported once
+    /// per run.
+    RailRun(SaturationState),
+}
+
+/// Pre-flight check of a recorded trace: every out-of-range code, every
+/// implausible jump and every sustained rail run, in trace order.
+///
+/// Out-of-range codes are left out of the jump check; a rejected jump does
+/// not move the reference, like [`PlausibilityGate::accept`].
+pub fn validate_trace(adc: &[u16], limits: &PlausibilityLimits) -> Vec<TraceIssue> {
+    let mut issues = Vec::new();
+    let mut gate = PlausibilityGate::new(limits.max_jump_x10);
+    let mut saturation = SaturationMonitor::new(limits.rail_run);
+    let mut was_stuck = false;
+
+    for (index, &code) in adc.iter().enumerate() {
+        let mut report = |kind| issues.push(TraceIssue { index, kind });
+        let rail = saturation.update(code);
+        let stuck = matches!(rail, SaturationState::StuckLow | SaturationState::StuckHigh);
+        if stuck && !was_stuck {
+            report(TraceIssueKind::RailRun(rail));
+        }
+        was_stuck = stuck;
+        if code > 4095 {
+            report(TraceIssueKind::OutOfRange(code));
+            continue;
+        }
+        let temp_x10 = adc_to_temp_x10(code);
+        let reference = gate.last_accepted;
+        if gate.accept(temp_x10).is_none() {
+            report(TraceIssueKind::Jump {
+                from_x10: reference.unwrap_or(temp_x10),
+                to_x10: temp_x10,
+            });
+        }
+    }
+    issues
+}
+
+/// `CH` independent pipelines sharing one threshold configuration.
+pub struct SensorBank<const CH: usize> {
+    channels: [Pipeline; CH],
+}
+
+impl<const CH: usize> SensorBank<CH> {
+    pub fn new(high_x10: TempX10, low_x10: TempX10) -> Self {
+        Self {
+            channels: core::array::from_fn(|_| Pipeline::new(high_x10, low_x10)),
+        }
+    }
+
+    /// Processes one ADC sample on `channel`. Panics if `channel >= CH`.
+    pub fn step(&mut self, channel: usize, adc_counts: u16) -> Option<State> {
+        self.channels[channel].step(adc_counts)
+    }
+
+    /// Sets the calibration applied to `channel` only. Panics if
+    /// `channel >= CH`.
+    pub fn set_calibration(&mut self, channel: usize, calibration: Calibration) {
+        self.channels[channel].set_calibration(calibration);
+    }
+
+    /// Pipeline of `channel`. Panics if `channel >= CH`.
+    pub fn channel(&self, channel: usize) -> &Pipeline {
+        &self.channels[channel]
+    }
+
+    /// True if any channel is currently `Unsafe`.
+    pub fn any_unsafe(&self) -> bool {
+        self.channels.iter().any(|p| p.state() == State::Unsafe)
+    }
+}
+
+/// Running statistics of raw ADC codes, e.g. over a stable reference period
+/// to feed [`Calibration::two_point`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdcStats {
+    count: u32,
+    sum: u64,
+    min: Option<u16>,
+    max: Option<u16>,
+}
+
+impl AdcStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count and sum saturate together, so after `u32::MAX` samples the
+    /// mean is that of the counted ones; min and max keep tracking.
+    pub fn observe(&mut self, adc_counts: u16) {
+        if let Some(count) = self.count.checked_add(1) {
+            self.count = count;
+            self.sum = self.sum.saturating_add(adc_counts as u64);
+        }
+        self.min = Some(self.min.map_or(adc_counts, |m| m.min(adc_counts)));
+        self.max = Some(self.max.map_or(adc_counts, |m| m.max(adc_counts)));
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Mean code rounded to nearest, or `None` before the first sample.
+    pub fn mean(&self) -> Option<u16> {
+        if self.count == 0 {
+            return None;
+        }
+        let count = self.count as u64;
+        Some(((self.sum + count / 2) / count) as u16)
+    }
+
+    pub fn min(&self) -> Option<u16> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<u16> {
+        self.max
+    }
+}
+
+/// Ring buffer of the last `K` raw ADC codes for post-fault trace dumps.
+#[derive(Clone, Debug)]
+pub struct SampleRecorder<const K: usize> {
+    buffer: [u16; K],
+    len: usize,
+    next: usize,
+}
+
+impl<const K: usize> SampleRecorder<K> {
+    pub fn new() -> Self {
+        Self {
+            buffer: [0; K],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Records `adc_counts`, overwriting the oldest code once full.
+    pub fn push(&mut self, adc_counts: u16) {
+        if K == 0 {
+            return;
+        }
+        self.buffer[self.next] = adc_counts;
+        self.next = (self.next + 1) % K;
+        self.len = (self.len + 1).min(K);
+    }
+
+    /// Retained codes, oldest first.
+    pub fn dump(&self) -> impl Iterator<Item = u16> + '_ {
+        let start = (self.next + K - self.len) % K.max(1);
+        (0..self.len).map(move |i| self.buffer[(start + i) % K])
+    }
+}
+
+impl<const K: usize> Default for SampleRecorder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compact ADC trace writer into a caller-provided buffer.
+///
+/// The first code is stored as two little-endian bytes; each later code as
+/// the zig-zag LEB128 varint of its difference to the previous one, so a
+/// slowly-varying trace costs about one byte per sample. Read it back with
+/// [`DeltaLog::decode`].
+#[derive(Debug)]
+pub struct DeltaLog<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    prev: Option<u16>,
+}
+
+impl<'a> DeltaLog<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            prev: None,
+        }
+    }
+
+    /// Appends `adc_counts` and returns the bytes written for it.
+    ///
+    /// If the encoding does not fit in the remaining buffer nothing is
+    /// written and the returned slice is empty.
+    pub fn encode(&mut self, adc_counts: u16) -> &[u8] {
+        let mut bytes = [0u8; 3];
+        let n = match self.prev {
+            None => {
+                bytes[..2].copy_from_slice(&adc_counts.to_le_bytes());
+                2
+            }
+            Some(prev) => {
+                let delta = adc_counts as i32 - prev as i32;
+                let mut zz = ((delta << 1) ^ (delta >> 31)) as u32;
+                let mut n = 0;
+                loop {
+                    let byte = (zz & 0x7f) as u8;
+                    zz >>= 7;
+                    if zz == 0 {
+                        bytes[n] = byte;
+                        break n + 1;
+                    }
+                    bytes[n] = byte | 0x80;
+                    n += 1;
+                }
+            }
+        };
+
+        let start = self.len;
+        if start + n > self.buf.len() {
+            return &[];
+        }
+        self.buf[start..start + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+        self.prev = Some(adc_counts);
+        &self.buf[start..start + n]
+    }
+
+    /// Encoded log written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Iterates the codes stored in `bytes`, stopping at a truncated entry.
+    pub fn decode(bytes: &[u8]) -> DeltaDecoder<'_> {
+        DeltaDecoder {
+            bytes,
+            pos: 0,
+            prev: None,
+        }
+    }
+}
+
+/// Iterator over the codes of a [`DeltaLog`], see [`DeltaLog::decode`].
+#[derive(Clone, Debug)]
+pub struct DeltaDecoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    prev: Option<u16>,
+}
+
+impl Iterator for DeltaDecoder<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        let code = match self.prev {
+            None => {
+                let first = self.bytes.get(self.pos..self.pos + 2)?;
+                self.pos += 2;
+                u16::from_le_bytes([first[0], first[1]])
+            }
+            Some(prev) => {
+                let mut zz = 0u32;
+                let mut shift = 0;
+                loop {
+                    let byte = *self.bytes.get(self.pos)?;
+                    self.pos += 1;
+                    zz |= ((byte & 0x7f) as u32) << shift;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                    shift += 7;
+                    if shift > 14 {
+                        return None;
+                    }
+                }
+                let delta = (zz >> 1) as i32 ^ -((zz & 1) as i32);
+                (prev as i32 + delta) as u16
+            }
+        };
+        self.prev = Some(code);
+        Some(code)
+    }
+}
+
+/// Sustained-drift detector over the last `K` (filtered) temperatures.
+///
+/// Both queries report `false` until `K` values have been pushed.
+#[derive(Clone, Debug)]
+pub struct MonotonicTrend<const K: usize> {
+    buffer: [TempX10; K],
+    len: usize,
+    next: usize,
+}
+
+impl<const K: usize> MonotonicTrend<K> {
+    pub fn new() -> Self {
+        const { assert!(K >= 2, "a trend needs at least 2 samples") };
+        Self {
+            buffer: [0; K],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    pub fn push(&mut self, temp_x10: TempX10) {
+        self.buffer[self.next] = temp_x10;
+        self.next = (self.next + 1) % K;
+        self.len = (self.len + 1).min(K);
+    }
+
+    /// The last `K` values are strictly increasing.
+    pub fn is_rising(&self) -> bool {
+        self.all_pairs(|older, newer| newer > older)
+    }
+
+    /// The last `K` values are strictly decreasing.
+    pub fn is_falling(&self) -> bool {
+        self.all_pairs(|older, newer| newer < older)
+    }
+
+    fn all_pairs(&self, ordered: impl Fn(TempX10, TempX10) -> bool) -> bool {
+        // Once full, `next` indexes the oldest value.
+        self.len == K
+            && (1..K).all(|i| {
+                ordered(
+                    self.buffer[(self.next + i - 1) % K],
+                    self.buffer[(self.next + i) % K],
+                )
+            })
+    }
+}
+
+impl<const K: usize> Default for MonotonicTrend<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Minimum and maximum over the last `W` samples, for long windows where a
+/// scan per sample is too slow.
+///
+/// Each bound is kept in a monotonic deque, so `push` is O(1) amortized and
+/// `min`/`max` are O(1). Before `W` samples both cover what was pushed.
+#[derive(Clone, Debug)]
+pub struct SlidingExtrema<const W: usize> {
+    mins: MonotonicDeque<W>,
+    maxs: MonotonicDeque<W>,
+    pushed: u64,
+}
+
+impl<const W: usize> SlidingExtrema<W> {
+    pub fn new() -> Self {
+        const { assert!(W >= 1, "window must hold at least 1 sample") };
+        Self {
+            mins: MonotonicDeque::new(),
+            maxs: MonotonicDeque::new(),
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, temp_x10: TempX10) {
+        let index = self.pushed;
+        self.pushed += 1;
+        self.mins.push(index, temp_x10, |kept, new| kept < new);
+        self.maxs.push(index, temp_x10, |kept, new| kept > new);
+    }
+
+    /// `None` until the first push.
+    pub fn min(&self) -> Option<TempX10> {
+        self.mins.front()
+    }
+
+    /// `None` until the first push.
+    pub fn max(&self) -> Option<TempX10> {
+        self.maxs.front()
+    }
+}
+
+impl<const W: usize> Default for SlidingExtrema<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fixed-capacity deque of `(index, value)` pairs whose values are ordered
+/// front to back by the `keeps` predicate passed to `push`.
+#[derive(Clone, Debug)]
+struct MonotonicDeque<const W: usize> {
+    buffer: [(u64, TempX10); W],
+    head: usize,
+    len: usize,
+}
+
+impl<const W: usize> MonotonicDeque<W> {
+    fn new() -> Self {
+        Self {
+            buffer: [(0, 0); W],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn front(&self) -> Option<TempX10> {
+        (self.len > 0).then(|| self.buffer[self.head].1)
+    }
+
+    /// Expires the entry that left the window, drops entries `temp_x10`
+    /// supersedes, then appends it. At most `W` entries survive: every
+    /// index left after expiry is one of the last `W - 1`.
+    fn push(&mut self, index: u64, temp_x10: TempX10, keeps: fn(TempX10, TempX10) -> bool) {
+        if self.len > 0 && index - self.buffer[self.head].0 >= W as u64 {
+            self.head = (self.head + 1) % W;
+            self.len -= 1;
+        }
+        while self.len > 0 && !keeps(self.buffer[(self.head + self.len - 1) % W].1, temp_x10) {
+            self.len -= 1;
+        }
+        self.buffer[(self.head + self.len) % W] = (index, temp_x10);
+        self.len += 1;
+    }
+}
+
+/// Sticky maximum/minimum of all observed temperatures.
+///
+/// Independent of any filter, so the record survives filter resets; clear it
+/// explicitly with [`PeakHold::reset`] at the start of a new run.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeakHold {
+    peak: Option<TempX10>,
+    trough: Option<TempX10>,
+}
+
+impl PeakHold {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(&mut self, temp_x10: TempX10) {
+        self.peak = Some(self.peak.map_or(temp_x10, |p| p.max(temp_x10)));
+        self.trough = Some(self.trough.map_or(temp_x10, |t| t.min(temp_x10)));
+    }
+
+    /// Highest temperature observed since the last reset.
+    pub fn peak(&self) -> Option<TempX10> {
+        self.peak
+    }
+
+    /// Lowest temperature observed since the last reset.
+    pub fn trough(&self) -> Option<TempX10> {
+        self.trough
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Detects Safe/Unsafe flapping: fires when `N` transitions fall within a
+/// sliding window of `window_cycles` cycles.
+///
+/// Cycle numbers are compared with wrapping arithmetic, so a free-running
+/// `u32` counter may overflow.
+#[derive(Clone, Debug)]
+pub struct OscillationDetector<const N: usize> {
+    window_cycles: u32,
+    cycles: [u32; N],
+    recorded: usize,
+    next: usize,
+}
+
+impl<const N: usize> OscillationDetector<N> {
+    pub fn new(window_cycles: u32) -> Self {
+        const { assert!(N > 0, "detector must track at least one transition") };
+        Self {
+            window_cycles,
+            cycles: [0; N],
+            recorded: 0,
+            next: 0,
+        }
+    }
+
+    /// Records a transition at `cycle`; returns true if this and the
+    /// previous `N - 1` transitions all happened within the window.
+    pub fn record_transition(&mut self, cycle: u32) -> bool {
+        self.cycles[self.next] = cycle;
+        self.next = (self.next + 1) % N;
+        self.recorded = (self.recorded + 1).min(N);
+        if self.recorded < N {
+            return false;
+        }
+        // After the write, `next` points at the oldest retained transition.
+        let oldest = self.cycles[self.next];
+        cycle.wrapping_sub(oldest) < self.window_cycles
+    }
+}
+
+/// Iterator adapter yielding `(index, new_state)` for each state change of
+/// an internal [`StateMachine`]. Created by [`TransitionsExt::transitions`].
+pub struct Transitions<I> {
+    iter: I,
+    machine: StateMachine,
+    index: usize,
+}
+
+impl<I: Iterator<Item = TempX10>> Iterator for Transitions<I> {
+    type Item = (usize, State);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for temp_x10 in self.iter.by_ref() {
+            let index = self.index;
+            self.index += 1;
+
+            let before = self.machine.state;
+            let after = self.machine.evaluate(temp_x10);
+            if after != before {
+                return Some((index, after));
+            }
+        }
+        None
+    }
+}
+
+/// Extension trait turning a stream of (filtered) temperatures into
+/// transition events.
+pub trait TransitionsExt: Iterator<Item = TempX10> + Sized {
+    /// Evaluates every item against the given thresholds, starting `Safe`,
+    /// and yields only the samples at which the state changes.
+    fn transitions(self, high_x10: TempX10, low_x10: TempX10) -> Transitions<Self> {
+        Transitions {
+            iter: self,
+            machine: StateMachine::new(high_x10, low_x10),
+            index: 0,
+        }
+    }
+}
+
+impl<I: Iterator<Item = TempX10>> TransitionsExt for I {}
@@ -0,0 +1,10 @@
+use tsim::harness::run_suite;
+
+#[test]
+fn run_suite_passes() {
+    let results = run_suite();
+    assert!(!results.is_empty());
+
+    let failures: Vec<_> = results.iter().filter(|r| !r.passed).collect();
+    assert!(failures.is_empty(), "failing cases: {failures:#?}");
+}
@@ -0,0 +1,59 @@
+//! `log` feature output. Kept out of `run_suite` because it installs a
+//! process-global logger; each integration test file runs in its own
+//! process.
+#![cfg(feature = "log")]
+
+use std::sync::Mutex;
+
+use tsim::{Pipeline, State};
+
+struct Capture(Mutex<Vec<log::Level>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.target().starts_with("tsim") {
+            self.0.lock().unwrap().push(record.level());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+fn warnings(levels: &[log::Level]) -> usize {
+    levels.iter().filter(|&&l| l == log::Level::Warn).count()
+}
+
+#[test]
+fn warns_once_on_trip() {
+    log::set_logger(&CAPTURE).expect("no other logger in this test process");
+    log::set_max_level(log::LevelFilter::Debug);
+    let records = || CAPTURE.0.lock().unwrap().clone();
+
+    let mut p = Pipeline::new(1000, 950);
+    for _ in 0..10 {
+        let _ = p.step(2048);
+    }
+    let steady = records();
+    assert_eq!(
+        steady.len(),
+        10,
+        "steady Safe operation must log each conversion"
+    );
+    assert_eq!(warnings(&steady), 0, "steady Safe operation must not warn");
+
+    for _ in 0..10 {
+        let _ = p.step(3549);
+    }
+    assert_eq!(p.state(), State::Unsafe);
+    assert_eq!(
+        warnings(&records()),
+        1,
+        "exactly one warning on the Safe -> Unsafe edge"
+    );
+}